@@ -0,0 +1,71 @@
+//! With the embedded-teal feature, generates the table of embedded teal templates
+//! from the .teal files in CAPI_TEAL_TEMPLATE_DIR (default: the teal project next to this crate).
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const TEMPLATE_DIR_VAR: &str = "CAPI_TEAL_TEMPLATE_DIR";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBEDDED_TEAL").is_none() {
+        return;
+    }
+    println!("cargo:rerun-if-env-changed={TEMPLATE_DIR_VAR}");
+
+    let dir = template_dir();
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut entries = vec![];
+    for (file_name, path) in teal_files(&dir) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        entries.push(format!("    ({file_name:?}, include_bytes!({path:?})),\n"));
+    }
+    let table = format!("&[\n{}]\n", entries.concat());
+
+    // unwrap: OUT_DIR is always set by cargo for build scripts
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("embedded_teal_templates.rs");
+    fs::write(&out, table).unwrap_or_else(|e| panic!("Couldn't write {}: {e}", out.display()));
+}
+
+fn template_dir() -> PathBuf {
+    let dir = match env::var_os(TEMPLATE_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        // unwrap: CARGO_MANIFEST_DIR is always set by cargo for build scripts
+        None => {
+            PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("../teal/teal_template")
+        }
+    };
+    dir.canonicalize().unwrap_or_else(|e| {
+        panic!(
+            "embedded-teal: teal template dir {} not found ({e}). Set {TEMPLATE_DIR_VAR} to the directory with the .teal templates.",
+            dir.display()
+        )
+    })
+}
+
+/// (file name without .teal, absolute path), sorted by name so the output is stable
+fn teal_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let read_dir = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Couldn't read teal template dir {}: {e}", dir.display()));
+    let mut files: Vec<(String, PathBuf)> = read_dir
+        .map(|entry| {
+            entry
+                .unwrap_or_else(|e| {
+                    panic!("Couldn't read teal template dir {}: {e}", dir.display())
+                })
+                .path()
+        })
+        .filter(|path| path.extension() == Some(OsStr::new("teal")))
+        .filter_map(|path| {
+            let file_name = path.file_stem()?.to_str()?.to_owned();
+            Some((file_name, path))
+        })
+        .collect();
+    files.sort();
+    files
+}
@@ -1,6 +1,19 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::{api::version::VersionedTealSourceTemplate, teal::TealSource};
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Contract {
     DaoCustomer,
     DaoAppApproval,
     DaoAppClear,
 }
+
+/// Renders contract templates with the values of a deployment (e.g. the capi app id),
+/// such that the result can be compared with the programs on chain.
+pub trait ContractRenderer {
+    fn render(
+        &self,
+        contract: Contract,
+        template: &VersionedTealSourceTemplate,
+    ) -> Result<TealSource>;
+}
@@ -0,0 +1,117 @@
+use crate::teal::TemplateValue;
+
+/// The branches of the DAO app, selected by the first arg of the app call.
+/// The args are defined only here: the approval TEAL gets them as template values ([DaoAppCall::template_values]),
+/// and the tx builders and the history classification use [DaoAppCall::arg].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DaoAppCall {
+    Setup,
+    Invest,
+    Lock,
+    Unlock,
+    Claim,
+    Drain,
+    Withdraw,
+    UpdateData,
+}
+
+impl DaoAppCall {
+    pub const ALL: [DaoAppCall; 8] = [
+        DaoAppCall::Setup,
+        DaoAppCall::Invest,
+        DaoAppCall::Lock,
+        DaoAppCall::Unlock,
+        DaoAppCall::Claim,
+        DaoAppCall::Drain,
+        DaoAppCall::Withdraw,
+        DaoAppCall::UpdateData,
+    ];
+
+    /// First arg of the app call
+    pub fn arg(&self) -> &'static [u8] {
+        match self {
+            DaoAppCall::Setup => b"setup",
+            DaoAppCall::Invest => b"invest",
+            DaoAppCall::Lock => b"lock",
+            DaoAppCall::Unlock => b"unlock",
+            DaoAppCall::Claim => b"claim",
+            DaoAppCall::Drain => b"drain",
+            DaoAppCall::Withdraw => b"withdraw",
+            DaoAppCall::UpdateData => b"update_data",
+        }
+    }
+
+    /// None if the arg isn't a branch of the app
+    pub fn from_arg(arg: &[u8]) -> Option<DaoAppCall> {
+        DaoAppCall::ALL
+            .iter()
+            .copied()
+            .find(|call| call.arg() == arg)
+    }
+
+    /// Placeholder of the arg in the approval template, e.g. `byte TMPL_CALL_INVEST`
+    pub fn template_key(&self) -> &'static str {
+        match self {
+            DaoAppCall::Setup => "TMPL_CALL_SETUP",
+            DaoAppCall::Invest => "TMPL_CALL_INVEST",
+            DaoAppCall::Lock => "TMPL_CALL_LOCK",
+            DaoAppCall::Unlock => "TMPL_CALL_UNLOCK",
+            DaoAppCall::Claim => "TMPL_CALL_CLAIM",
+            DaoAppCall::Drain => "TMPL_CALL_DRAIN",
+            DaoAppCall::Withdraw => "TMPL_CALL_WITHDRAW",
+            DaoAppCall::UpdateData => "TMPL_CALL_UPDATE_DATA",
+        }
+    }
+
+    /// The args of all the branches, to render the approval template with
+    pub fn template_values() -> Vec<(&'static str, TemplateValue)> {
+        DaoAppCall::ALL
+            .iter()
+            .map(|call| {
+                (
+                    call.template_key(),
+                    TemplateValue::Bytes(call.arg().to_vec()),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::dao_app_call::DaoAppCall,
+        teal::{render_template, TealSourceTemplate},
+    };
+    use anyhow::Result;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_args_are_unique_and_parse_back() {
+        let args: HashSet<&[u8]> = DaoAppCall::ALL.iter().map(|call| call.arg()).collect();
+        assert_eq!(DaoAppCall::ALL.len(), args.len());
+
+        for call in DaoAppCall::ALL {
+            assert_eq!(Some(call), DaoAppCall::from_arg(call.arg()));
+        }
+        assert_eq!(None, DaoAppCall::from_arg(b"not_a_call"));
+    }
+
+    #[test]
+    fn test_template_values_render_the_args() -> Result<()> {
+        let template = TealSourceTemplate(
+            DaoAppCall::ALL
+                .iter()
+                .map(|call| format!("byte {}\n", call.template_key()))
+                .collect::<String>()
+                .into_bytes(),
+        );
+
+        let rendered = render_template(&template, &DaoAppCall::template_values())?;
+
+        assert!(rendered
+            .0
+            .starts_with(b"byte 0x7365747570\nbyte 0x696e76657374\n"));
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 pub mod contract;
+pub mod dao_app_call;
 pub mod teal_api;
 #[allow(clippy::module_inception)]
 pub mod version;
+pub mod upgrade_plan;
+pub mod teal_compiler;
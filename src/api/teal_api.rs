@@ -1,60 +1,257 @@
 use super::contract::Contract;
 use crate::{
-    api::version::{Version, VersionedTealSourceTemplate, Versions},
-    teal::load_teal_template,
+    api::version::{CapiVersions, Version, VersionedTealSourceTemplate, Versions},
+    teal::{load_teal_template, TealSourceTemplate},
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
 
-pub struct TealFileLoader {}
+/// Provides the versioned templates of the contracts.
+/// Implement this to supply templates from other sources (e.g. fetched or generated by the app).
+pub trait TealTemplateLoader {
+    /// None if the version of the contract isn't known
+    fn template(
+        &self,
+        contract: Contract,
+        version: Version,
+    ) -> Result<Option<VersionedTealSourceTemplate>>;
+
+    /// None if there are no versions of the contract
+    fn latest_version(&self, contract: Contract) -> Option<Version>;
+
+    /// Latest versions of the app programs (what's stored in the app's global state)
+    fn last_versions(&self) -> Result<Versions> {
+        Ok(Versions {
+            app_approval: self.required_latest_version(Contract::DaoAppApproval)?,
+            app_clear: self.required_latest_version(Contract::DaoAppClear)?,
+        })
+    }
+
+    /// Latest versions of all the contracts, including the customer escrow
+    fn last_capi_versions(&self) -> Result<CapiVersions> {
+        Ok(CapiVersions {
+            app_approval: self.required_latest_version(Contract::DaoAppApproval)?,
+            app_clear: self.required_latest_version(Contract::DaoAppClear)?,
+            escrow: self.required_latest_version(Contract::DaoCustomer)?,
+        })
+    }
+
+    fn required_latest_version(&self, contract: Contract) -> Result<Version> {
+        self.latest_version(contract)
+            .ok_or_else(|| anyhow!("No versions registered for contract: {contract:?}"))
+    }
+}
+
+/// The contract versions known to this library and the files (without .teal) with their templates
+const TEAL_TEMPLATES: &[(Contract, Version, &str)] = &[
+    (Contract::DaoCustomer, Version(1), "customer_escrow"),
+    (Contract::DaoAppApproval, Version(1), "dao_app_approval"),
+    (Contract::DaoAppClear, Version(1), "dao_app_clear"),
+];
+
+/// The .teal files of the template dir (see build.rs), bundled in the binary
+#[cfg(feature = "embedded-teal")]
+const EMBEDDED_TEAL_TEMPLATES: &[(&str, &[u8])] =
+    include!(concat!(env!("OUT_DIR"), "/embedded_teal_templates.rs"));
+
+/// Template bundled in the binary, for environments without access to the teal project (WASM, packaged binaries).
+/// file_name without .teal
+#[cfg(feature = "embedded-teal")]
+pub fn embedded_teal_template(file_name: &str) -> Result<TealSourceTemplate> {
+    log::debug!("Loading embedded teal template: {file_name}");
+    EMBEDDED_TEAL_TEMPLATES
+        .iter()
+        .find(|(name, _)| *name == file_name)
+        .map(|(_, bytes)| TealSourceTemplate(bytes.to_vec()))
+        .ok_or_else(|| anyhow!("No embedded teal template: {file_name}"))
+}
+
+/// Maps contract versions to template file names
+#[derive(Debug, Clone, Default)]
+pub struct TealTemplateRegistry {
+    templates: HashMap<(Contract, Version), String>,
+}
+
+impl TealTemplateRegistry {
+    pub fn new() -> TealTemplateRegistry {
+        TealTemplateRegistry::default()
+    }
+
+    /// The versions bundled with this library
+    pub fn capi() -> TealTemplateRegistry {
+        let mut registry = TealTemplateRegistry::new();
+        for (contract, version, file_name) in TEAL_TEMPLATES {
+            registry.register(*contract, *version, file_name);
+        }
+        registry
+    }
+
+    /// Replaces the file of the version if it was already registered
+    pub fn register(&mut self, contract: Contract, version: Version, file_name: &str) {
+        self.templates
+            .insert((contract, version), file_name.to_owned());
+    }
+
+    pub fn file_name(&self, contract: Contract, version: Version) -> Option<&str> {
+        self.templates
+            .get(&(contract, version))
+            .map(|file_name| file_name.as_str())
+    }
+
+    /// Registered versions of the contract, ascending
+    pub fn versions(&self, contract: Contract) -> Vec<Version> {
+        let mut versions: Vec<Version> = self
+            .templates
+            .keys()
+            .filter(|(c, _)| *c == contract)
+            .map(|(_, version)| *version)
+            .collect();
+        versions.sort();
+        versions
+    }
+
+    pub fn latest_version(&self, contract: Contract) -> Option<Version> {
+        self.versions(contract).last().copied()
+    }
+}
+
+/// Where the templates are read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TealSourceBackend {
+    /// The teal project directory, at runtime. For development (picks up template changes without rebuilding).
+    Filesystem,
+    /// Bundled in the binary at compile time
+    #[cfg(feature = "embedded-teal")]
+    Embedded,
+}
+
+/// Loads the templates of the registry's versions from the backend
+#[derive(Debug, Clone)]
+pub struct TealFileLoader {
+    backend: TealSourceBackend,
+    registry: TealTemplateRegistry,
+}
+
+/// Embedded templates if the crate was built with them, filesystem otherwise
+impl Default for TealFileLoader {
+    fn default() -> Self {
+        #[cfg(feature = "embedded-teal")]
+        return TealFileLoader::embedded();
+        #[cfg(not(feature = "embedded-teal"))]
+        return TealFileLoader::filesystem();
+    }
+}
 
 impl TealFileLoader {
-    pub fn last_versions(&self) -> Versions {
-        Versions {
-            app_approval: Version(1),
-            app_clear: Version(1),
+    pub fn filesystem() -> TealFileLoader {
+        TealFileLoader {
+            backend: TealSourceBackend::Filesystem,
+            registry: TealTemplateRegistry::capi(),
+        }
+    }
+
+    /// The templates are embedded from CAPI_TEAL_TEMPLATE_DIR at build time (see build.rs)
+    #[cfg(feature = "embedded-teal")]
+    pub fn embedded() -> TealFileLoader {
+        TealFileLoader {
+            backend: TealSourceBackend::Embedded,
+            registry: TealTemplateRegistry::capi(),
+        }
+    }
+
+    pub fn with_registry(self, registry: TealTemplateRegistry) -> TealFileLoader {
+        TealFileLoader { registry, ..self }
+    }
+
+    pub fn backend(&self) -> TealSourceBackend {
+        self.backend
+    }
+
+    pub fn registry(&self) -> &TealTemplateRegistry {
+        &self.registry
+    }
+
+    fn load(&self, file_name: &str) -> Result<TealSourceTemplate> {
+        match self.backend {
+            TealSourceBackend::Filesystem => load_teal_template(file_name),
+            #[cfg(feature = "embedded-teal")]
+            TealSourceBackend::Embedded => embedded_teal_template(file_name),
         }
     }
+}
 
-    pub fn template(
+impl TealTemplateLoader for TealFileLoader {
+    fn template(
         &self,
         contract: Contract,
         version: Version,
     ) -> Result<Option<VersionedTealSourceTemplate>> {
-        match contract {
-            Contract::DaoCustomer => dao_customer_teal(version),
-            Contract::DaoAppApproval => dao_app_approval_teal(version),
-            Contract::DaoAppClear => dao_app_clear_teal(version),
+        match self.registry.file_name(contract, version) {
+            Some(file_name) => Ok(Some(VersionedTealSourceTemplate {
+                version,
+                template: self.load(file_name)?,
+            })),
+            None => Ok(None),
         }
     }
-}
 
-fn dao_customer_teal(version: Version) -> Result<Option<VersionedTealSourceTemplate>> {
-    Ok(match version.0 {
-        1 => Some(load_versioned_teal_template(version, "customer_escrow")?),
-        _ => None,
-    })
+    fn latest_version(&self, contract: Contract) -> Option<Version> {
+        self.registry.latest_version(contract)
+    }
 }
 
-fn dao_app_approval_teal(version: Version) -> Result<Option<VersionedTealSourceTemplate>> {
-    Ok(match version.0 {
-        1 => Some(load_versioned_teal_template(version, "dao_app_approval")?),
-        _ => None,
-    })
-}
+#[cfg(test)]
+mod tests {
+    use crate::api::{
+        contract::Contract,
+        teal_api::{TealFileLoader, TealTemplateLoader, TealTemplateRegistry},
+        version::{CapiVersions, Version, Versions},
+    };
+    use anyhow::Result;
 
-fn dao_app_clear_teal(version: Version) -> Result<Option<VersionedTealSourceTemplate>> {
-    Ok(match version.0 {
-        1 => Some(load_versioned_teal_template(version, "dao_app_clear")?),
-        _ => None,
-    })
-}
+    #[test]
+    fn test_latest_versions() -> Result<()> {
+        let mut registry = TealTemplateRegistry::capi();
+        registry.register(Contract::DaoAppApproval, Version(3), "dao_app_approval_v3");
+        registry.register(Contract::DaoAppApproval, Version(2), "dao_app_approval_v2");
+        registry.register(Contract::DaoCustomer, Version(2), "customer_escrow_v2");
+        let loader = TealFileLoader::filesystem().with_registry(registry);
+
+        assert_eq!(
+            vec![Version(1), Version(2), Version(3)],
+            loader.registry().versions(Contract::DaoAppApproval)
+        );
+        assert_eq!(
+            Versions {
+                app_approval: Version(3),
+                app_clear: Version(1),
+            },
+            loader.last_versions()?
+        );
+        assert_eq!(
+            CapiVersions {
+                app_approval: Version(3),
+                app_clear: Version(1),
+                escrow: Version(2),
+            },
+            loader.last_capi_versions()?
+        );
+        assert_eq!(None, loader.template(Contract::DaoAppClear, Version(2))?);
+
+        let empty = TealFileLoader::filesystem().with_registry(TealTemplateRegistry::new());
+        assert!(empty.last_capi_versions().is_err());
+        Ok(())
+    }
 
-fn load_versioned_teal_template(
-    version: Version,
-    file_name: &str,
-) -> Result<VersionedTealSourceTemplate> {
-    Ok(VersionedTealSourceTemplate {
-        version,
-        template: load_teal_template(file_name)?,
-    })
+    #[cfg(feature = "embedded-teal")]
+    #[test]
+    fn test_embedded_loader_has_all_registered_templates() -> Result<()> {
+        use crate::api::teal_api::TEAL_TEMPLATES;
+
+        let loader = TealFileLoader::embedded();
+        for (contract, version, _) in TEAL_TEMPLATES {
+            assert!(loader.template(*contract, *version)?.is_some());
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,295 @@
+use super::{
+    contract::Contract,
+    version::{Version, VersionedContractAccount, VersionedTealSourceTemplate},
+};
+use crate::{
+    chain::chain_client::ChainClient,
+    models::hashable::hash,
+    teal::{render_template, TealSource, TemplateValue},
+};
+use algonaut::{
+    core::{Address, CompiledTeal},
+    crypto::HashDigest,
+    transaction::contract_account::ContractAccount,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Domain separation prefix of program hashes, see
+/// https://developer.algorand.org/docs/get-details/dapps/smart-contracts/frontend/smartsigs
+const PROGRAM_HASH_PREFIX: &[u8] = b"Program";
+
+/// Compiles TEAL source to bytecode
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait TealCompiler {
+    async fn compile(&self, source: &TealSource) -> Result<Vec<u8>>;
+}
+
+/// Compiles with algod's compile endpoint (the node has to have the developer API enabled)
+pub struct AlgodTealCompiler<'a, C: ChainClient> {
+    client: &'a C,
+}
+
+impl<'a, C: ChainClient> AlgodTealCompiler<'a, C> {
+    pub fn new(client: &'a C) -> AlgodTealCompiler<'a, C> {
+        AlgodTealCompiler { client }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<'a, C: ChainClient + Sync> TealCompiler for AlgodTealCompiler<'a, C> {
+    async fn compile(&self, source: &TealSource) -> Result<Vec<u8>> {
+        Ok(self.client.teal_compile(&source.0).await?.0)
+    }
+}
+
+/// Returns previously recorded bytecode (e.g. captured from algod), to test without a node.
+#[derive(Debug, Default)]
+pub struct RecordedTealCompiler {
+    fixtures: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl RecordedTealCompiler {
+    pub fn new() -> RecordedTealCompiler {
+        RecordedTealCompiler::default()
+    }
+
+    pub fn record(&mut self, source: &TealSource, program: Vec<u8>) {
+        self.fixtures.insert(source.0.clone(), program);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TealCompiler for RecordedTealCompiler {
+    async fn compile(&self, source: &TealSource) -> Result<Vec<u8>> {
+        self.fixtures.get(&source.0).cloned().ok_or_else(|| {
+            anyhow!(
+                "No recorded program for source: {}",
+                String::from_utf8_lossy(&source.0)
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledProgram {
+    pub version: Version,
+    pub program: Vec<u8>,
+    /// sha512_256("Program" || program). It's also the logic sig (escrow) address.
+    pub hash: HashDigest,
+}
+
+impl CompiledProgram {
+    pub fn new(version: Version, program: Vec<u8>) -> CompiledProgram {
+        let hash = program_hash(&program);
+        CompiledProgram {
+            version,
+            program,
+            hash,
+        }
+    }
+
+    /// Address of the logic sig account
+    pub fn address(&self) -> Address {
+        Address(self.hash.0)
+    }
+
+    pub fn contract_account(&self) -> ContractAccount {
+        ContractAccount::new(CompiledTeal(self.program.clone()))
+    }
+
+    pub fn versioned_contract_account(&self) -> VersionedContractAccount {
+        VersionedContractAccount {
+            version: self.version,
+            account: self.contract_account(),
+        }
+    }
+}
+
+pub fn program_hash(program: &[u8]) -> HashDigest {
+    hash(&[PROGRAM_HASH_PREFIX, program].concat())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CompileKey {
+    contract: Contract,
+    version: Version,
+    /// Hash of the template bytes: loaders can return different templates for the same version
+    /// (e.g. a development template that's edited, or templates of different sources)
+    template_hash: [u8; 32],
+    params: Vec<(String, TemplateValue)>,
+}
+
+/// Renders and compiles templates, caching the programs by template (version and content) and render params
+/// (compiling is a network request and the escrows are compiled for every DAO operation).
+pub struct CachedTealCompiler<T: TealCompiler> {
+    compiler: T,
+    cache: Mutex<HashMap<CompileKey, CompiledProgram>>,
+}
+
+impl<T: TealCompiler> CachedTealCompiler<T> {
+    pub fn new(compiler: T) -> CachedTealCompiler<T> {
+        CachedTealCompiler {
+            compiler,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn compile_template(
+        &self,
+        contract: Contract,
+        template: &VersionedTealSourceTemplate,
+        params: &[(&str, TemplateValue)],
+    ) -> Result<CompiledProgram> {
+        let mut key_params: Vec<(String, TemplateValue)> = params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        // the order in which the params are passed doesn't matter for the result
+        key_params.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        let key = CompileKey {
+            contract,
+            version: template.version,
+            template_hash: hash(&template.template.0).0,
+            params: key_params,
+        };
+
+        if let Some(program) = self.cache().get(&key) {
+            return Ok(program.clone());
+        }
+
+        let source = render_template(&template.template, params)?;
+        let program = CompiledProgram::new(template.version, self.compiler.compile(&source).await?);
+        self.cache().insert(key, program.clone());
+        Ok(program)
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, HashMap<CompileKey, CompiledProgram>> {
+        // the lock is only held for map operations, which don't panic
+        self.cache
+            .lock()
+            .expect("Compiled programs cache lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::{
+            contract::Contract,
+            teal_compiler::{program_hash, CachedTealCompiler, RecordedTealCompiler, TealCompiler},
+            version::{Version, VersionedTealSourceTemplate},
+        },
+        teal::{TealSource, TealSourceTemplate, TemplateValue},
+    };
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use data_encoding::HEXLOWER;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_program_hash() -> Result<()> {
+        // expected: hashlib.new("sha512_256", b"Program" + program).hexdigest() (python)
+        let program = HEXLOWER.decode(b"0220010122")?;
+        let hash = program_hash(&program);
+        assert_eq!(
+            "c389e16b4638a6a03767a7244ebcc87b7b455c099dc50a3d3b528bd3b807f73c",
+            HEXLOWER.encode(&hash.0)
+        );
+        Ok(())
+    }
+
+    struct CountingCompiler {
+        recorded: RecordedTealCompiler,
+        count: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TealCompiler for CountingCompiler {
+        async fn compile(&self, source: &TealSource) -> Result<Vec<u8>> {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            self.recorded.compile(source).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compile_template_is_cached() -> Result<()> {
+        let mut recorded = RecordedTealCompiler::new();
+        recorded.record(&TealSource(b"int 1\nint 2".to_vec()), vec![1, 2]);
+        recorded.record(&TealSource(b"int 3\nint 2".to_vec()), vec![3, 2]);
+        recorded.record(&TealSource(b"int 2\nint 1".to_vec()), vec![2, 1]);
+        let compiler = CachedTealCompiler::new(CountingCompiler {
+            recorded,
+            count: AtomicUsize::new(0),
+        });
+        let template = VersionedTealSourceTemplate::new(
+            TealSourceTemplate(b"int TMPL_A\nint TMPL_B".to_vec()),
+            Version(1),
+        );
+
+        let program = compiler
+            .compile_template(
+                Contract::DaoCustomer,
+                &template,
+                &[
+                    ("TMPL_A", TemplateValue::U64(1)),
+                    ("TMPL_B", TemplateValue::U64(2)),
+                ],
+            )
+            .await?;
+        assert_eq!(vec![1, 2], program.program);
+        assert_eq!(program_hash(&[1, 2]), program.hash);
+        assert_eq!(program.hash.0, program.address().0);
+
+        // same params, in a different order: cached
+        let cached = compiler
+            .compile_template(
+                Contract::DaoCustomer,
+                &template,
+                &[
+                    ("TMPL_B", TemplateValue::U64(2)),
+                    ("TMPL_A", TemplateValue::U64(1)),
+                ],
+            )
+            .await?;
+        assert_eq!(program, cached);
+        assert_eq!(1, compiler.compiler.count.load(Ordering::SeqCst));
+
+        let other = compiler
+            .compile_template(
+                Contract::DaoCustomer,
+                &template,
+                &[
+                    ("TMPL_A", TemplateValue::U64(3)),
+                    ("TMPL_B", TemplateValue::U64(2)),
+                ],
+            )
+            .await?;
+        assert_eq!(vec![3, 2], other.program);
+        assert_eq!(2, compiler.compiler.count.load(Ordering::SeqCst));
+
+        // same version and params, different template content: not cached
+        let edited_template = VersionedTealSourceTemplate::new(
+            TealSourceTemplate(b"int TMPL_B\nint TMPL_A".to_vec()),
+            Version(1),
+        );
+        let edited = compiler
+            .compile_template(
+                Contract::DaoCustomer,
+                &edited_template,
+                &[
+                    ("TMPL_A", TemplateValue::U64(1)),
+                    ("TMPL_B", TemplateValue::U64(2)),
+                ],
+            )
+            .await?;
+        assert_eq!(vec![2, 1], edited.program);
+        assert_eq!(3, compiler.compiler.count.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use super::{
+    contract::{Contract, ContractRenderer},
+    teal_api::TealTemplateLoader,
+    version::{versions_to_bytes, Version, Versions},
+};
+use crate::{
+    state::{
+        dao_app_state::{dao_global_state_from_app, global_state_layout},
+        dao_state_schema::StateScope,
+    },
+    teal::TealSource,
+};
+use algonaut::model::algod::v2::Application;
+use anyhow::{anyhow, Result};
+use std::fmt::{self, Display, Formatter};
+
+/// A DAO app program, as it has to be set by the update app call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractUpgrade {
+    pub contract: Contract,
+    pub current: Version,
+    /// The current version if the contract isn't behind
+    pub target: Version,
+    /// The rendered template of the target version.
+    /// Also set if the contract isn't behind: the update app call replaces both programs.
+    pub program: TealSource,
+}
+
+impl ContractUpgrade {
+    pub fn is_behind(&self) -> bool {
+        self.target != self.current
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradePlan {
+    pub approval: ContractUpgrade,
+    pub clear: ContractUpgrade,
+}
+
+impl UpgradePlan {
+    pub fn is_up_to_date(&self) -> bool {
+        !self.approval.is_behind() && !self.clear.is_behind()
+    }
+
+    pub fn current(&self) -> Versions {
+        Versions {
+            app_approval: self.approval.current,
+            app_clear: self.clear.current,
+        }
+    }
+
+    pub fn target(&self) -> Versions {
+        Versions {
+            app_approval: self.approval.target,
+            app_clear: self.clear.target,
+        }
+    }
+
+    /// Versions state value to store with the update app call
+    pub fn target_versions_bytes(&self) -> Result<Vec<u8>> {
+        versions_to_bytes(self.target())
+    }
+}
+
+/// Why a DAO can't be upgraded to the latest versions (it'd have to be recreated)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpgradeImpossible {
+    /// We don't know the state of the target approval version
+    UnknownLayout(Version),
+    /// The target approval version needs more keys than the app's schema, which is set at creation and can't be updated.
+    /// Sizes are (byte slices, uints).
+    SchemaTooSmall {
+        scope: StateScope,
+        needed: (u64, u64),
+        available: (u64, u64),
+    },
+}
+
+impl Display for UpgradeImpossible {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UpgradeImpossible::UnknownLayout(version) => {
+                write!(f, "No state layout for app approval version: {version:?}")
+            }
+            UpgradeImpossible::SchemaTooSmall {
+                scope,
+                needed,
+                available,
+            } => write!(
+                f,
+                "{scope:?} state needs: {needed:?} (byte slices, uints), the app's schema has: {available:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UpgradeImpossible {}
+
+/// Compares the DAO's program versions with the latest ones of the loader.
+/// Never plans downgrades: if the DAO has a newer version than the loader knows, it's kept.
+/// Errors with [UpgradeImpossible] if the state of the target version doesn't fit in the app's schema.
+pub fn plan_dao_upgrade(
+    app: &Application,
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+) -> Result<UpgradePlan> {
+    let dao = dao_global_state_from_app(app)?;
+    let latest = loader.last_versions()?;
+
+    let approval = plan_contract_upgrade(
+        loader,
+        renderer,
+        Contract::DaoAppApproval,
+        dao.app_approval_version,
+        latest.app_approval,
+    )?;
+    let clear = plan_contract_upgrade(
+        loader,
+        renderer,
+        Contract::DaoAppClear,
+        dao.app_clear_version,
+        latest.app_clear,
+    )?;
+
+    check_schema_fits(app, approval.target)?;
+
+    Ok(UpgradePlan { approval, clear })
+}
+
+fn plan_contract_upgrade(
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+    contract: Contract,
+    current: Version,
+    latest: Version,
+) -> Result<ContractUpgrade> {
+    let target = current.max(latest);
+    let template = loader
+        .template(contract, target)?
+        .ok_or_else(|| anyhow!("No template for version: {target:?} of contract: {contract:?}"))?;
+    Ok(ContractUpgrade {
+        contract,
+        current,
+        target,
+        program: renderer.render(contract, &template)?,
+    })
+}
+
+fn check_schema_fits(
+    app: &Application,
+    approval_version: Version,
+) -> Result<(), UpgradeImpossible> {
+    let layout = global_state_layout(approval_version)
+        .ok_or(UpgradeImpossible::UnknownLayout(approval_version))?;
+    for (scope, schema) in [
+        (StateScope::Global, &app.params.global_state_schema),
+        (StateScope::Local, &app.params.local_state_schema),
+    ] {
+        let needed = layout.schema_size(scope);
+        // algod omits empty schemas
+        let available = schema
+            .as_ref()
+            .map(|schema| (schema.num_byte_slice, schema.num_uint))
+            .unwrap_or_default();
+        if needed.0 > available.0 || needed.1 > available.1 {
+            return Err(UpgradeImpossible::SchemaTooSmall {
+                scope,
+                needed,
+                available,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::{
+            upgrade_plan::{plan_dao_upgrade, UpgradeImpossible},
+            version::{bytes_to_versions, Version, Versions},
+        },
+        state::{
+            dao_app_state::CentralAppGlobalState,
+            dao_state_schema::{StateScope, LOCAL_SCHEMA_NUM_BYTE_SLICES, LOCAL_SCHEMA_NUM_INTS},
+        },
+        test_util::{self, InMemoryLoader, PassthroughRenderer},
+    };
+    use algonaut::model::algod::v2::{Application, ApplicationParams, ApplicationStateSchema};
+    use anyhow::Result;
+
+    #[test]
+    fn test_plan_upgrade_of_outdated_dao() -> Result<()> {
+        let plan = plan_dao_upgrade(
+            &dao_app(Version(1), Version(1))?,
+            &loader(Version(1), Version(2)),
+            &PassthroughRenderer,
+        )?;
+
+        assert!(!plan.is_up_to_date());
+        assert!(plan.clear.is_behind());
+        assert_eq!(Version(1), plan.clear.current);
+        assert_eq!(Version(2), plan.clear.target);
+        assert_eq!("DaoAppClear v2", plan.clear.program.to_string());
+        // not behind, but the update needs its program too
+        assert!(!plan.approval.is_behind());
+        assert_eq!("DaoAppApproval v1", plan.approval.program.to_string());
+
+        let expected_target = Versions {
+            app_approval: Version(1),
+            app_clear: Version(2),
+        };
+        assert_eq!(expected_target, plan.target());
+        assert_eq!(
+            expected_target,
+            bytes_to_versions(&plan.target_versions_bytes()?)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_upgrade_of_up_to_date_dao() -> Result<()> {
+        // newer than the loader's: not downgraded
+        let plan = plan_dao_upgrade(
+            &dao_app(Version(1), Version(3))?,
+            &loader(Version(1), Version(2)),
+            &PassthroughRenderer,
+        )?;
+
+        assert!(plan.is_up_to_date());
+        assert_eq!(plan.current(), plan.target());
+        assert_eq!("DaoAppClear v3", plan.clear.program.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_upgrade_to_unknown_state_layout_is_impossible() -> Result<()> {
+        let res = plan_dao_upgrade(
+            &dao_app(Version(1), Version(1))?,
+            &loader(Version(2), Version(1)),
+            &PassthroughRenderer,
+        );
+
+        let err = res.unwrap_err();
+        assert_eq!(
+            Some(&UpgradeImpossible::UnknownLayout(Version(2))),
+            err.downcast_ref()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_upgrade_with_too_small_schema_is_impossible() -> Result<()> {
+        let app = dao_app(Version(1), Version(1))?;
+        let app = Application {
+            params: ApplicationParams {
+                local_state_schema: Some(ApplicationStateSchema {
+                    num_byte_slice: LOCAL_SCHEMA_NUM_BYTE_SLICES,
+                    num_uint: LOCAL_SCHEMA_NUM_INTS - 1,
+                }),
+                ..app.params
+            },
+            ..app
+        };
+
+        let res = plan_dao_upgrade(&app, &loader(Version(1), Version(2)), &PassthroughRenderer);
+
+        let err = res.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(UpgradeImpossible::SchemaTooSmall {
+                scope: StateScope::Local,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    /// Has templates for newer versions too
+    fn loader(approval: Version, clear: Version) -> InMemoryLoader {
+        InMemoryLoader {
+            latest: Versions {
+                app_approval: approval,
+                app_clear: clear,
+            },
+            max_version: Version(3),
+        }
+    }
+
+    fn dao_app(approval: Version, clear: Version) -> Result<Application> {
+        test_util::dao_app(
+            123,
+            &CentralAppGlobalState {
+                app_approval_version: approval,
+                app_clear_version: clear,
+                ..CentralAppGlobalState::for_tests()
+            },
+        )
+    }
+}
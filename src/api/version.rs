@@ -8,7 +8,7 @@ use algonaut::{
 use anyhow::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Version(pub u32);
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
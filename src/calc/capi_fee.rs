@@ -0,0 +1,120 @@
+use crate::models::{
+    capi_deps::CapiAssetDaoDeps,
+    funds::FundsAmount,
+    shares_percentage::{PercentageMath, Rounding, SHARES_PERCENTAGE_PRECISION},
+};
+use anyhow::{anyhow, Result};
+use std::convert::TryInto;
+
+/// A customer payment, split in what goes to capi and what's added to the DAO's `received`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapiFeeSplit {
+    pub fee: FundsAmount,
+    pub net: FundsAmount,
+}
+
+/// Capi fee charged when draining `payment`.
+/// Like in TEAL: payment * fee percentage (integer encoding) / precision, rounded down.
+pub fn capi_fee(payment: FundsAmount, deps: &CapiAssetDaoDeps) -> Result<FundsAmount> {
+    Ok(payment.mul_percentage(deps.escrow_percentage, Rounding::Floor)?)
+}
+
+pub fn split_customer_payment(
+    payment: FundsAmount,
+    deps: &CapiAssetDaoDeps,
+) -> Result<CapiFeeSplit> {
+    let fee = capi_fee(payment, deps)?;
+    Ok(CapiFeeSplit {
+        fee,
+        // fee <= payment
+        net: FundsAmount::new(payment.val() - fee.val()),
+    })
+}
+
+/// Smallest payment whose net (after the capi fee) is `net`.
+/// The integer counterpart of [crate::util::decimal_util::DecimalExt::amount_to_pay_to_get_self_after_deducting_fee].
+pub fn gross_for_net(net: FundsAmount, deps: &CapiAssetDaoDeps) -> Result<FundsAmount> {
+    if net.val() == 0 {
+        return Ok(net);
+    }
+    let precision = SHARES_PERCENTAGE_PRECISION as u128;
+    let fee = deps.escrow_percentage.to_u64()? as u128;
+    if fee == precision {
+        return Err(anyhow!("Fee is 100%: no payment yields a net of {net}"));
+    }
+
+    // net(gross) = gross - floor(gross * fee / precision) = ceil(gross * (precision - fee) / precision),
+    // which increases in steps of 0 or 1, so there's always a gross with exactly `net`. The smallest is:
+    let gross = (net.val() as u128 - 1) * precision / (precision - fee) + 1;
+
+    Ok(FundsAmount::new(gross.try_into().map_err(|_| {
+        anyhow!("Payment needed for a net of {net} doesn't fit in an amount")
+    })?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calc::capi_fee::{capi_fee, gross_for_net, split_customer_payment},
+        models::{
+            capi_deps::{CapiAddress, CapiAssetDaoDeps},
+            funds::FundsAmount,
+        },
+    };
+    use algonaut::core::Address;
+    use anyhow::Result;
+    use rust_decimal::Decimal;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_capi_fee_rounds_down() -> Result<()> {
+        let deps = deps("0.03")?;
+
+        // 3% of 1_999 = 59.97
+        assert_eq!(
+            FundsAmount::new(59),
+            capi_fee(FundsAmount::new(1_999), &deps)?
+        );
+
+        let split = split_customer_payment(FundsAmount::new(1_999), &deps)?;
+        assert_eq!(FundsAmount::new(59), split.fee);
+        assert_eq!(FundsAmount::new(1_940), split.net);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gross_for_net_is_smallest_payment_with_net() -> Result<()> {
+        for fee in ["0", "0.0001", "0.005", "0.03", "0.5", "0.9999"] {
+            let deps = deps(fee)?;
+            for net in (0..2_000).map(FundsAmount::new) {
+                let gross = gross_for_net(net, &deps)?;
+
+                assert_eq!(net, split_customer_payment(gross, &deps)?.net);
+                if gross.val() > 0 {
+                    let smaller = FundsAmount::new(gross.val() - 1);
+                    assert!(split_customer_payment(smaller, &deps)?.net.val() < net.val());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gross_for_net_fails_with_100_percent_fee() -> Result<()> {
+        let deps = deps("1")?;
+
+        assert!(gross_for_net(FundsAmount::new(1), &deps).is_err());
+
+        Ok(())
+    }
+
+    fn deps(fee: &str) -> Result<CapiAssetDaoDeps> {
+        let fee: Decimal = fee.parse().unwrap();
+        Ok(CapiAssetDaoDeps {
+            escrow_percentage: fee.try_into()?,
+            address: CapiAddress(Address([1; 32])),
+        })
+    }
+}
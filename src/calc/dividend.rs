@@ -0,0 +1,227 @@
+use crate::{
+    checked::CheckedSub,
+    models::{
+        funds::FundsAmount,
+        share_amount::ShareAmount,
+        shares_percentage::{SharesPercentage, SHARES_PERCENTAGE_PRECISION},
+    },
+    state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState},
+};
+use anyhow::{anyhow, Result};
+use std::convert::TryInto;
+
+/// Total dividend the investor is entitled to since the DAO was created,
+/// i.e. including what was already claimed (and `claimed_init`).
+///
+/// Mirrors the TEAL integer arithmetic: the result is rounded down once, at the end,
+/// and the intermediate results are wide (like mulw / divmodw in TEAL), so they can't overflow.
+pub fn entitled_dividend(
+    received: FundsAmount,
+    shares: ShareAmount,
+    share_supply: ShareAmount,
+    investors_share: SharesPercentage,
+) -> Result<FundsAmount> {
+    if share_supply.val() == 0 {
+        return Err(anyhow!("Share supply must be greater than 0"));
+    }
+    if shares > share_supply {
+        return Err(anyhow!(
+            "Shares: {shares} must be less or equal to share supply: {share_supply}"
+        ));
+    }
+
+    let supply = share_supply.val() as u128;
+    let share = investors_share.to_u64()? as u128;
+    let precision = SHARES_PERCENTAGE_PRECISION as u128;
+
+    // entitled = floor(received * shares * share / (supply * precision))
+    // split in steps such that no intermediate result overflows u128
+    let received_shares = received.val() as u128 * shares.val() as u128;
+    let quotient = received_shares / supply;
+    let remainder = received_shares % supply;
+    let quotient_share = quotient * share;
+    let entitled = quotient_share / precision
+        + (quotient_share % precision * supply + remainder * share) / (supply * precision);
+
+    // entitled <= received, since shares <= supply and investors share <= 1
+    let entitled: u64 = entitled.try_into()?;
+    Ok(FundsAmount::new(entitled))
+}
+
+/// Dividend the investor can claim now.
+/// `share_supply` is the total supply of the DAO's shares asset (not stored in the app state).
+pub fn claimable_dividend(
+    dao: &CentralAppGlobalState,
+    investor: &CentralAppInvestorState,
+    share_supply: ShareAmount,
+) -> Result<FundsAmount> {
+    let entitled = entitled_dividend(
+        dao.received,
+        investor.shares,
+        share_supply,
+        dao.investors_share,
+    )?;
+    // TEAL fails if claimed > entitled, which shouldn't be possible as received only increases
+    Ok(entitled.sub(&investor.claimed)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calc::dividend::{claimable_dividend, entitled_dividend},
+        models::{
+            funds::FundsAmount, share_amount::ShareAmount, shares_percentage::SharesPercentage,
+        },
+        state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState},
+    };
+    use anyhow::Result;
+    use proptest::prelude::*;
+    use rust_decimal::Decimal;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_entitled_dividend_rounds_down() -> Result<()> {
+        // 1000 * 1/3 * 0.5 = 166.66..
+        let entitled = entitled_dividend(
+            FundsAmount::new(1_000),
+            ShareAmount::new(1),
+            ShareAmount::new(3),
+            Decimal::new(5, 1).try_into()?,
+        )?;
+
+        assert_eq!(FundsAmount::new(166), entitled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entitled_dividend_does_not_overflow() -> Result<()> {
+        let entitled = entitled_dividend(
+            FundsAmount::new(u64::MAX),
+            ShareAmount::new(u64::MAX),
+            ShareAmount::new(u64::MAX),
+            Decimal::ONE.try_into()?,
+        )?;
+
+        assert_eq!(FundsAmount::new(u64::MAX), entitled);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_subtracts_claimed() -> Result<()> {
+        // entitled: 1000 * 10/100 * 0.4 = 40
+        let claimable = claimable_dividend(
+            &dao_state(1_000),
+            &investor_state(10, 15),
+            ShareAmount::new(100),
+        )?;
+
+        assert_eq!(FundsAmount::new(25), claimable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_claimed_more_than_entitled_fails() {
+        let res = claimable_dividend(
+            &dao_state(1_000),
+            &investor_state(10, 41),
+            ShareAmount::new(100),
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_claimable_dividend_without_shares_is_zero() -> Result<()> {
+        let claimable = claimable_dividend(
+            &dao_state(1_000),
+            &investor_state(0, 0),
+            ShareAmount::new(100),
+        )?;
+
+        assert_eq!(FundsAmount::new(0), claimable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entitled_dividend_fails_with_0_supply() {
+        let res = entitled_dividend(
+            FundsAmount::new(1_000),
+            ShareAmount::new(0),
+            ShareAmount::new(0),
+            Decimal::ONE.try_into().unwrap(),
+        );
+
+        assert!(res.is_err());
+    }
+
+    /// Reference model: exact decimal arithmetic, rounded down at the end.
+    /// Ranges are restricted such that the values fit in Decimal's precision.
+    fn reference_entitled(
+        received: u64,
+        shares: u64,
+        supply: u64,
+        investors_share: SharesPercentage,
+    ) -> u64 {
+        let entitled = Decimal::from(received) * Decimal::from(shares) * investors_share.value()
+            / Decimal::from(supply);
+        entitled.floor().try_into().unwrap()
+    }
+
+    fn shares_percentage() -> impl Strategy<Value = SharesPercentage> {
+        (0..=10_000u64).prop_map(|value| value.try_into().unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_entitled_dividend_matches_reference(
+            received in 0..1_000_000_000_000u64,
+            (supply, shares) in (1..1_000_000_000u64).prop_flat_map(|supply| (Just(supply), 0..=supply)),
+            investors_share in shares_percentage(),
+        ) {
+            let entitled = entitled_dividend(
+                FundsAmount::new(received),
+                ShareAmount::new(shares),
+                ShareAmount::new(supply),
+                investors_share,
+            ).unwrap();
+
+            prop_assert_eq!(reference_entitled(received, shares, supply, investors_share), entitled.val());
+        }
+
+        #[test]
+        fn prop_entitled_dividend_never_exceeds_received(
+            received in any::<u64>(),
+            (supply, shares) in (1..=u64::MAX).prop_flat_map(|supply| (Just(supply), 0..=supply)),
+            investors_share in shares_percentage(),
+        ) {
+            let entitled = entitled_dividend(
+                FundsAmount::new(received),
+                ShareAmount::new(shares),
+                ShareAmount::new(supply),
+                investors_share,
+            ).unwrap();
+
+            prop_assert!(entitled.val() <= received);
+        }
+    }
+
+    fn dao_state(received: u64) -> CentralAppGlobalState {
+        CentralAppGlobalState {
+            received: FundsAmount::new(received),
+            ..CentralAppGlobalState::for_tests()
+        }
+    }
+
+    fn investor_state(shares: u64, claimed: u64) -> CentralAppInvestorState {
+        CentralAppInvestorState {
+            shares: ShareAmount::new(shares),
+            claimed: FundsAmount::new(claimed),
+            claimed_init: FundsAmount::new(0),
+            signed_prospectus: None,
+        }
+    }
+}
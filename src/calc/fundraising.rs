@@ -0,0 +1,218 @@
+use crate::{
+    checked::{CheckedSub, SaturatingAdd},
+    models::{share_amount::ShareAmount, timestamp::Timestamp},
+    state::dao_app_state::CentralAppGlobalState,
+};
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundraisingStatus {
+    /// Before the end date, target not reached yet
+    Open,
+    /// Before the end date, target reached (investing is still possible)
+    TargetReached,
+    /// End date passed without reaching the target - investors can reclaim their funds
+    Failed,
+    /// End date passed and the target was reached
+    Ended,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundraisingProgress {
+    pub status: FundraisingStatus,
+    /// raised / min target as a ratio (not a percentage) - can be > 1, if more than the target was raised
+    pub raised_ratio: Decimal,
+    /// 0 if the end date passed
+    pub remaining_time: Duration,
+    /// Shares that haven't been sold yet
+    pub remaining_shares: ShareAmount,
+}
+
+pub fn fundraising_status(dao: &CentralAppGlobalState, now: Timestamp) -> FundraisingStatus {
+    let target_reached = dao.raised.val() >= dao.min_funds_target.val();
+    let ended = now.0 >= dao.min_funds_target_end_date.0;
+    match (ended, target_reached) {
+        (false, false) => FundraisingStatus::Open,
+        (false, true) => FundraisingStatus::TargetReached,
+        (true, false) => FundraisingStatus::Failed,
+        (true, true) => FundraisingStatus::Ended,
+    }
+}
+
+/// `shares_for_investors` is the amount of shares that were put up for sale when setting up the DAO
+/// (it's not stored in the app state).
+pub fn fundraising_progress(
+    dao: &CentralAppGlobalState,
+    shares_for_investors: ShareAmount,
+    now: Timestamp,
+) -> Result<FundraisingProgress> {
+    let raised_ratio = if dao.min_funds_target.val() == 0 {
+        // nothing to reach
+        Decimal::ONE
+    } else {
+        dao.raised.as_decimal() / dao.min_funds_target.as_decimal()
+    };
+
+    let remaining_time = Duration::from_secs(dao.min_funds_target_end_date.0.saturating_sub(now.0));
+
+    if dao.share_price.val() == 0 {
+        return Err(anyhow!("Invalid state: share price is 0"));
+    }
+    // investing pays exactly share price * amount, so this division has no remainder
+    let sold_shares = ShareAmount::new(dao.raised.val() / dao.share_price.val());
+    let remaining_shares = shares_for_investors.sub(&sold_shares)?;
+
+    Ok(FundraisingProgress {
+        status: fundraising_status(dao, now),
+        raised_ratio,
+        remaining_time,
+        remaining_shares,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvestBoundsViolation {
+    BelowMin {
+        min: ShareAmount,
+        amount: ShareAmount,
+    },
+    /// `total` are the shares the investor would have after investing (u64::MAX if it overflows)
+    AboveMax {
+        max: ShareAmount,
+        total: ShareAmount,
+    },
+}
+
+/// Checks buying `amount` shares against the min / max invest amounts set by the DAO.
+/// The min applies to each investment, the max to the investor's total (`already_locked` + `amount`).
+pub fn invest_bounds_violations(
+    dao: &CentralAppGlobalState,
+    already_locked: ShareAmount,
+    amount: ShareAmount,
+) -> Vec<InvestBoundsViolation> {
+    let mut violations = vec![];
+    if amount < dao.min_invest_amount {
+        violations.push(InvestBoundsViolation::BelowMin {
+            min: dao.min_invest_amount,
+            amount,
+        });
+    }
+    // can't be within max if it doesn't even fit in an amount
+    let total = already_locked.saturating_add(&amount);
+    if total > dao.max_invest_amount {
+        violations.push(InvestBoundsViolation::AboveMax {
+            max: dao.max_invest_amount,
+            total,
+        });
+    }
+    violations
+}
+
+/// Whether buying `amount` shares is within the min / max invest amounts set by the DAO
+pub fn is_within_invest_bounds(
+    dao: &CentralAppGlobalState,
+    already_locked: ShareAmount,
+    amount: ShareAmount,
+) -> bool {
+    invest_bounds_violations(dao, already_locked, amount).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calc::fundraising::{
+            fundraising_progress, fundraising_status, is_within_invest_bounds, FundraisingStatus,
+        },
+        models::{funds::FundsAmount, share_amount::ShareAmount, timestamp::Timestamp},
+        state::dao_app_state::CentralAppGlobalState,
+    };
+    use anyhow::Result;
+    use rust_decimal::Decimal;
+    use std::time::Duration;
+
+    #[test]
+    fn test_fundraising_status() -> Result<()> {
+        let end_date = Timestamp(1_700_000_000);
+        let before_end = Timestamp(end_date.0 - 1);
+
+        let not_reached = dao_state(FundsAmount::new(10), FundsAmount::new(100), end_date);
+        let reached = dao_state(FundsAmount::new(100), FundsAmount::new(100), end_date);
+
+        assert_eq!(
+            FundraisingStatus::Open,
+            fundraising_status(&not_reached, before_end)
+        );
+        assert_eq!(
+            FundraisingStatus::Failed,
+            fundraising_status(&not_reached, end_date)
+        );
+        assert_eq!(
+            FundraisingStatus::TargetReached,
+            fundraising_status(&reached, before_end)
+        );
+        assert_eq!(
+            FundraisingStatus::Ended,
+            fundraising_status(&reached, end_date)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fundraising_progress() -> Result<()> {
+        // share price is 10: 15 shares sold
+        let dao = dao_state(
+            FundsAmount::new(150),
+            FundsAmount::new(200),
+            Timestamp(1_000),
+        );
+
+        let progress = fundraising_progress(&dao, ShareAmount::new(40), Timestamp(400))?;
+
+        assert_eq!(FundraisingStatus::Open, progress.status);
+        assert_eq!("0.75".parse::<Decimal>()?, progress.raised_ratio);
+        assert_eq!(Duration::from_secs(600), progress.remaining_time);
+        assert_eq!(ShareAmount::new(25), progress.remaining_shares);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invest_bounds() -> Result<()> {
+        // min 1, max 100
+        let dao = dao_state(FundsAmount::new(0), FundsAmount::new(100), Timestamp(1_000));
+
+        let none = ShareAmount::new(0);
+        assert!(!is_within_invest_bounds(&dao, none, ShareAmount::new(0)));
+        assert!(is_within_invest_bounds(&dao, none, ShareAmount::new(1)));
+        assert!(is_within_invest_bounds(&dao, none, ShareAmount::new(100)));
+        assert!(!is_within_invest_bounds(&dao, none, ShareAmount::new(101)));
+
+        // the max is for the investor's total shares
+        let locked = ShareAmount::new(95);
+        assert!(is_within_invest_bounds(&dao, locked, ShareAmount::new(5)));
+        assert!(!is_within_invest_bounds(&dao, locked, ShareAmount::new(6)));
+        assert!(!is_within_invest_bounds(
+            &dao,
+            ShareAmount::new(u64::MAX),
+            ShareAmount::new(1)
+        ));
+
+        Ok(())
+    }
+
+    fn dao_state(
+        raised: FundsAmount,
+        target: FundsAmount,
+        end_date: Timestamp,
+    ) -> CentralAppGlobalState {
+        CentralAppGlobalState {
+            min_funds_target: target,
+            min_funds_target_end_date: end_date,
+            raised,
+            ..CentralAppGlobalState::for_tests()
+        }
+    }
+}
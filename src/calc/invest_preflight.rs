@@ -0,0 +1,182 @@
+use super::fundraising::{
+    fundraising_status, invest_bounds_violations, FundraisingStatus, InvestBoundsViolation,
+};
+use crate::{
+    checked::CheckedMulOther,
+    models::{funds::FundsAmount, share_amount::ShareAmount, timestamp::Timestamp},
+    state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState, Prospectus},
+};
+
+/// Reasons why an investment would be rejected (by TEAL or by our rules)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvestViolation {
+    BelowMinInvestAmount {
+        min: ShareAmount,
+        amount: ShareAmount,
+    },
+    /// `total` are the shares the investor would have after investing (including the already locked ones)
+    AboveMaxInvestAmount {
+        max: ShareAmount,
+        total: ShareAmount,
+    },
+    /// The end date of the raise passed
+    RaiseClosed(FundraisingStatus),
+    /// The DAO has a prospectus, which has to be acked to invest
+    ProspectusNotAcked,
+    /// The acked prospectus isn't the current one
+    ProspectusMismatch {
+        expected_hash: String,
+        acked_hash: String,
+    },
+    /// share price * amount doesn't fit in an amount
+    FundsOverflow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvestPreflight {
+    pub violations: Vec<InvestViolation>,
+    /// Funds to pay for the shares (share price * amount). None if it overflows.
+    pub funds_needed: Option<FundsAmount>,
+}
+
+impl InvestPreflight {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Checks everything we know before building the invest transactions.
+/// `investor` is the investor's state if they already have shares locked in the DAO.
+/// `acked_prospectus` is the prospectus the user acknowledged in the UI.
+pub fn validate_investment(
+    dao: &CentralAppGlobalState,
+    investor: Option<&CentralAppInvestorState>,
+    amount: ShareAmount,
+    acked_prospectus: Option<&Prospectus>,
+    now: Timestamp,
+) -> InvestPreflight {
+    let mut violations = vec![];
+
+    let already_locked = investor
+        .map(|i| i.shares)
+        .unwrap_or_else(|| ShareAmount::new(0));
+    for violation in invest_bounds_violations(dao, already_locked, amount) {
+        violations.push(match violation {
+            InvestBoundsViolation::BelowMin { min, amount } => {
+                InvestViolation::BelowMinInvestAmount { min, amount }
+            }
+            InvestBoundsViolation::AboveMax { max, total } => {
+                InvestViolation::AboveMaxInvestAmount { max, total }
+            }
+        });
+    }
+
+    let status = fundraising_status(dao, now);
+    match status {
+        FundraisingStatus::Open | FundraisingStatus::TargetReached => {}
+        FundraisingStatus::Failed | FundraisingStatus::Ended => {
+            violations.push(InvestViolation::RaiseClosed(status))
+        }
+    }
+
+    if let Some(prospectus) = &dao.prospectus {
+        match acked_prospectus {
+            None => violations.push(InvestViolation::ProspectusNotAcked),
+            Some(acked) if acked.hash != prospectus.hash => {
+                violations.push(InvestViolation::ProspectusMismatch {
+                    expected_hash: prospectus.hash.clone(),
+                    acked_hash: acked.hash.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    let funds_needed = dao.share_price.mul(amount.val()).ok();
+    if funds_needed.is_none() {
+        violations.push(InvestViolation::FundsOverflow);
+    }
+
+    InvestPreflight {
+        violations,
+        funds_needed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calc::{
+            fundraising::FundraisingStatus,
+            invest_preflight::{validate_investment, InvestViolation},
+        },
+        models::{funds::FundsAmount, share_amount::ShareAmount, timestamp::Timestamp},
+        state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState, Prospectus},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_valid_investment() -> Result<()> {
+        let dao = dao_state();
+
+        let res = validate_investment(
+            &dao,
+            None,
+            ShareAmount::new(10),
+            dao.prospectus.as_ref(),
+            Timestamp(500),
+        );
+
+        assert!(res.is_valid());
+        assert_eq!(Some(FundsAmount::new(100)), res.funds_needed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_investment_violations() -> Result<()> {
+        let dao = dao_state();
+        let investor = CentralAppInvestorState {
+            shares: ShareAmount::new(95),
+            claimed: FundsAmount::new(0),
+            claimed_init: FundsAmount::new(0),
+            signed_prospectus: None,
+        };
+        let other_prospectus = Prospectus::new(b"other", "https://prospectus".to_owned());
+
+        let res = validate_investment(
+            &dao,
+            Some(&investor),
+            ShareAmount::new(10),
+            Some(&other_prospectus),
+            Timestamp(1_000),
+        );
+
+        assert_eq!(
+            vec![
+                InvestViolation::AboveMaxInvestAmount {
+                    max: ShareAmount::new(100),
+                    total: ShareAmount::new(105)
+                },
+                InvestViolation::RaiseClosed(FundraisingStatus::Failed),
+                InvestViolation::ProspectusMismatch {
+                    expected_hash: dao.prospectus.unwrap().hash,
+                    acked_hash: other_prospectus.hash
+                },
+            ],
+            res.violations
+        );
+
+        Ok(())
+    }
+
+    fn dao_state() -> CentralAppGlobalState {
+        CentralAppGlobalState {
+            prospectus: Some(Prospectus::new(
+                b"prospectus",
+                "https://prospectus".to_owned(),
+            )),
+            ..CentralAppGlobalState::for_tests()
+        }
+    }
+}
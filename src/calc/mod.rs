@@ -0,0 +1,4 @@
+pub mod dividend;
+pub mod fundraising;
+pub mod invest_preflight;
+pub mod capi_fee;
@@ -0,0 +1,78 @@
+use algonaut::{
+    algod::v2::Algod,
+    core::{Address, CompiledTeal},
+    error::ServiceError,
+    model::algod::v2::{Account, Application, PendingTransaction, TransactionResponse},
+    transaction::SignedTransaction,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// The node operations the state readers and tx utilities need.
+/// Implemented by [Algod] and, for tests, [super::fake_chain_client::FakeChainClient].
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait ChainClient {
+    async fn application_information(&self, app_id: u64) -> Result<Application>;
+    /// Like [ChainClient::application_information], but None if the app doesn't exist
+    /// (e.g. it was deleted: accounts can still be opted in to it). Other errors are returned.
+    async fn find_application(&self, app_id: u64) -> Result<Option<Application>>;
+    async fn account_information(&self, address: &Address) -> Result<Account>;
+    async fn broadcast_signed_transaction(
+        &self,
+        tx: &SignedTransaction,
+    ) -> Result<TransactionResponse>;
+    async fn broadcast_signed_transactions(
+        &self,
+        txs: &[SignedTransaction],
+    ) -> Result<TransactionResponse>;
+    async fn pending_transaction_with_id(&self, tx_id: &str) -> Result<PendingTransaction>;
+    async fn teal_compile(&self, source: &[u8]) -> Result<CompiledTeal>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ChainClient for Algod {
+    async fn application_information(&self, app_id: u64) -> Result<Application> {
+        Ok(self.application_information(app_id).await?)
+    }
+
+    async fn find_application(&self, app_id: u64) -> Result<Option<Application>> {
+        match self.application_information(app_id).await {
+            Ok(app) => Ok(Some(app)),
+            Err(e) if is_app_not_found(&e) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn account_information(&self, address: &Address) -> Result<Account> {
+        Ok(self.account_information(address).await?)
+    }
+
+    async fn broadcast_signed_transaction(
+        &self,
+        tx: &SignedTransaction,
+    ) -> Result<TransactionResponse> {
+        Ok(self.broadcast_signed_transaction(tx).await?)
+    }
+
+    async fn broadcast_signed_transactions(
+        &self,
+        txs: &[SignedTransaction],
+    ) -> Result<TransactionResponse> {
+        Ok(self.broadcast_signed_transactions(txs).await?)
+    }
+
+    async fn pending_transaction_with_id(&self, tx_id: &str) -> Result<PendingTransaction> {
+        Ok(self.pending_transaction_with_id(tx_id).await?)
+    }
+
+    async fn teal_compile(&self, source: &[u8]) -> Result<CompiledTeal> {
+        Ok(self.teal_compile(source, None).await?)
+    }
+}
+
+/// algod responds 404 with this message for apps that don't exist
+fn is_app_not_found(e: &ServiceError) -> bool {
+    e.to_string().contains("application does not exist")
+}
@@ -0,0 +1,158 @@
+use super::chain_client::ChainClient;
+use algonaut::{
+    core::{Address, CompiledTeal},
+    model::algod::v2::{
+        Account, Application, ApplicationParams, PendingTransaction, TealKeyValue,
+        TransactionResponse,
+    },
+    transaction::SignedTransaction,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Mutex};
+
+/// In-memory [ChainClient], to test code that reads from / writes to the chain without a node.
+/// Queries for data that wasn't added return an error, like the node does for unknown ids.
+#[derive(Debug, Default)]
+pub struct FakeChainClient {
+    state: Mutex<FakeChainState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeChainState {
+    apps: HashMap<u64, Application>,
+    // app id -> error message
+    app_errors: HashMap<u64, String>,
+    accounts: Vec<Account>,
+    pending_txs: HashMap<String, PendingTransaction>,
+    compiled: HashMap<Vec<u8>, CompiledTeal>,
+    broadcasted: Vec<SignedTransaction>,
+}
+
+impl FakeChainClient {
+    pub fn new() -> FakeChainClient {
+        FakeChainClient::default()
+    }
+
+    pub fn add_app(&self, app: Application) {
+        self.state().apps.insert(app.id, app);
+    }
+
+    /// Queries for the app fail with the message (e.g. a node error), as opposed to not finding the app
+    pub fn fail_app(&self, app_id: u64, message: &str) {
+        self.state().app_errors.insert(app_id, message.to_owned());
+    }
+
+    /// Convenience to add an app where only the global state and creator matter
+    pub fn add_app_with_global_state(
+        &self,
+        app_id: u64,
+        creator: Address,
+        global_state: Vec<TealKeyValue>,
+    ) {
+        self.add_app(Application {
+            id: app_id,
+            params: ApplicationParams {
+                approval_program: vec![],
+                clear_state_program: vec![],
+                creator,
+                global_state,
+                global_state_schema: None,
+                local_state_schema: None,
+            },
+        })
+    }
+
+    pub fn add_account(&self, account: Account) {
+        let mut state = self.state();
+        state.accounts.retain(|a| a.address != account.address);
+        state.accounts.push(account);
+    }
+
+    pub fn add_pending_transaction(&self, tx_id: &str, pending_tx: PendingTransaction) {
+        self.state()
+            .pending_txs
+            .insert(tx_id.to_owned(), pending_tx);
+    }
+
+    pub fn add_compiled_teal(&self, source: &[u8], compiled: CompiledTeal) {
+        self.state().compiled.insert(source.to_vec(), compiled);
+    }
+
+    /// Transactions passed to the broadcast functions, in order
+    pub fn broadcasted(&self) -> Vec<SignedTransaction> {
+        self.state().broadcasted.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeChainState> {
+        // a panic while holding the lock is a test failure anyway
+        self.state.lock().expect("Fake chain state lock poisoned")
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ChainClient for FakeChainClient {
+    async fn application_information(&self, app_id: u64) -> Result<Application> {
+        self.find_application(app_id)
+            .await?
+            .ok_or_else(|| anyhow!("Application not found: {app_id}"))
+    }
+
+    async fn find_application(&self, app_id: u64) -> Result<Option<Application>> {
+        let state = self.state();
+        match state.app_errors.get(&app_id) {
+            Some(message) => Err(anyhow!("{message}")),
+            None => Ok(state.apps.get(&app_id).cloned()),
+        }
+    }
+
+    async fn account_information(&self, address: &Address) -> Result<Account> {
+        self.state()
+            .accounts
+            .iter()
+            .find(|a| &a.address == address)
+            .cloned()
+            .ok_or_else(|| anyhow!("Account not found: {address}"))
+    }
+
+    async fn broadcast_signed_transaction(
+        &self,
+        tx: &SignedTransaction,
+    ) -> Result<TransactionResponse> {
+        self.state().broadcasted.push(tx.clone());
+        Ok(TransactionResponse {
+            tx_id: tx.transaction_id.clone(),
+        })
+    }
+
+    async fn broadcast_signed_transactions(
+        &self,
+        txs: &[SignedTransaction],
+    ) -> Result<TransactionResponse> {
+        let first = txs
+            .first()
+            .ok_or_else(|| anyhow!("Can't broadcast empty tx group"))?;
+        self.state().broadcasted.extend(txs.iter().cloned());
+        // like algod, returns the id of the first tx of the group
+        Ok(TransactionResponse {
+            tx_id: first.transaction_id.clone(),
+        })
+    }
+
+    async fn pending_transaction_with_id(&self, tx_id: &str) -> Result<PendingTransaction> {
+        self.state()
+            .pending_txs
+            .get(tx_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Pending transaction not found: {tx_id}"))
+    }
+
+    async fn teal_compile(&self, source: &[u8]) -> Result<CompiledTeal> {
+        self.state()
+            .compiled
+            .get(source)
+            .cloned()
+            .ok_or_else(|| anyhow!("No compiled teal added for source"))
+    }
+}
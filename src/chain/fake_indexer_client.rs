@@ -0,0 +1,151 @@
+use super::indexer_client::{
+    AppCallDeltas, AppCallDeltasPage, IndexedTx, IndexedTxKind, IndexedTxPage, IndexerClient,
+};
+use crate::models::timestamp::Timestamp;
+use algonaut::core::Address;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// In-memory [IndexerClient].
+/// App state is added as app calls with their state deltas, like the indexer returns it.
+#[derive(Debug, Default)]
+pub struct FakeIndexerClient {
+    state: Mutex<FakeIndexerState>,
+}
+
+#[derive(Debug, Default)]
+struct FakeIndexerState {
+    block_timestamps: BTreeMap<u64, Timestamp>,
+    // app id -> calls, in the order they're added
+    app_calls: BTreeMap<u64, Vec<AppCallDeltas>>,
+    txs: Vec<IndexedTx>,
+}
+
+impl FakeIndexerClient {
+    pub fn new() -> FakeIndexerClient {
+        FakeIndexerClient::default()
+    }
+
+    /// The last added round is the current round
+    pub fn add_block(&self, round: u64, timestamp: Timestamp) {
+        self.state().block_timestamps.insert(round, timestamp);
+    }
+
+    /// Calls have to be added in confirmation order
+    pub fn add_app_call(&self, app_id: u64, call: AppCallDeltas) {
+        self.state().app_calls.entry(app_id).or_default().push(call);
+    }
+
+    /// Txs are returned in the order they're added
+    pub fn add_tx(&self, tx: IndexedTx) {
+        self.state().txs.push(tx);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeIndexerState> {
+        // a panic while holding the lock is a test failure anyway
+        self.state.lock().expect("Fake indexer state lock poisoned")
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IndexerClient for FakeIndexerClient {
+    async fn current_round(&self) -> Result<u64> {
+        self.state()
+            .block_timestamps
+            .keys()
+            .next_back()
+            .copied()
+            .ok_or_else(|| anyhow!("No blocks added"))
+    }
+
+    async fn block_timestamp(&self, round: u64) -> Result<Timestamp> {
+        self.state()
+            .block_timestamps
+            .get(&round)
+            .copied()
+            .ok_or_else(|| anyhow!("Block not found: {round}"))
+    }
+
+    async fn app_calls(
+        &self,
+        app_id: u64,
+        max_round: u64,
+        next_token: Option<String>,
+    ) -> Result<AppCallDeltasPage> {
+        let calls: Vec<AppCallDeltas> = self
+            .state()
+            .app_calls
+            .get(&app_id)
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter(|call| call.round <= max_round)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let (page, next_token) = paginate(calls, next_token, APP_CALLS_PAGE_SIZE)?;
+        Ok(AppCallDeltasPage {
+            calls: page,
+            next_token,
+        })
+    }
+
+    async fn txs_with_address(
+        &self,
+        address: &Address,
+        next_token: Option<String>,
+        limit: u64,
+    ) -> Result<IndexedTxPage> {
+        let txs: Vec<IndexedTx> = self
+            .state()
+            .txs
+            .iter()
+            .filter(|tx| involves(tx, address))
+            .cloned()
+            .collect();
+        let (page, next_token) = paginate(txs, next_token, limit as usize)?;
+        Ok(IndexedTxPage {
+            txs: page,
+            next_token,
+        })
+    }
+
+    async fn group_txs(&self, round: u64, group: &str) -> Result<Vec<IndexedTx>> {
+        Ok(self
+            .state()
+            .txs
+            .iter()
+            .filter(|tx| tx.round == Some(round) && tx.group.as_deref() == Some(group))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Small, so tests exercise the pagination
+const APP_CALLS_PAGE_SIZE: usize = 2;
+
+/// The token is just the index of the next item
+fn paginate<T>(
+    items: Vec<T>,
+    next_token: Option<String>,
+    limit: usize,
+) -> Result<(Vec<T>, Option<String>)> {
+    let start: usize = match next_token {
+        Some(token) => token.parse()?,
+        None => 0,
+    };
+    let has_more = items.len() > start + limit;
+    let page = items.into_iter().skip(start).take(limit).collect();
+    Ok((page, has_more.then(|| (start + limit).to_string())))
+}
+
+fn involves(tx: &IndexedTx, address: &Address) -> bool {
+    let is_receiver = match &tx.kind {
+        IndexedTxKind::AssetTransfer { receiver, .. } => receiver == address,
+        _ => false,
+    };
+    &tx.sender == address || is_receiver || tx.inner_txs.iter().any(|tx| involves(tx, address))
+}
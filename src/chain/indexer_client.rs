@@ -0,0 +1,492 @@
+use crate::{
+    models::{timestamp::Timestamp, tx_id::TxId},
+    state::app_state::{TEAL_VALUE_TYPE_BYTES, TEAL_VALUE_TYPE_UINT},
+};
+use algonaut::{
+    core::{Address, Round},
+    indexer::v2::Indexer,
+    model::{
+        algod::v2::{TealKeyValue, TealValue},
+        indexer::v2::{EvalDeltaKeyValue, QueryTransaction, Transaction},
+    },
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+/// Max app calls per indexer request
+const APP_CALLS_PAGE_SIZE: u64 = 1000;
+
+/// App state as of a past round.
+/// The key values have the algod types, so they can be passed directly to the state decoders.
+#[derive(Debug, Clone)]
+pub struct HistoricalAppState {
+    pub creator: Address,
+    pub global_state: Vec<TealKeyValue>,
+}
+
+/// Change of a state key by an app call (the indexer's global-state-delta / local-state-delta entries)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDelta {
+    /// base64, like [TealKeyValue::key]
+    pub key: String,
+    pub action: StateDeltaAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateDeltaAction {
+    SetBytes(Vec<u8>),
+    SetUint(u64),
+    Delete,
+}
+
+/// Delta setting the key to the value
+impl From<&TealKeyValue> for StateDelta {
+    fn from(kv: &TealKeyValue) -> Self {
+        StateDelta {
+            key: kv.key.clone(),
+            action: if kv.value.value_type == TEAL_VALUE_TYPE_UINT {
+                StateDeltaAction::SetUint(kv.value.uint)
+            } else {
+                StateDeltaAction::SetBytes(kv.value.bytes.clone())
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppCallCompletion {
+    NoOp,
+    OptIn,
+    CloseOut,
+    ClearState,
+    Update,
+    Delete,
+}
+
+/// An app call, with the state changes it made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppCallDeltas {
+    pub round: u64,
+    pub sender: Address,
+    pub completion: AppCallCompletion,
+    /// Whether this is the call that created the app
+    pub creates_app: bool,
+    pub global_delta: Vec<StateDelta>,
+    pub local_deltas: Vec<(Address, Vec<StateDelta>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppCallDeltasPage {
+    pub calls: Vec<AppCallDeltas>,
+    /// Pass to the next query to get the next page. None if there are no more calls.
+    pub next_token: Option<String>,
+}
+
+/// A confirmed transaction, with only the fields we need
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedTx {
+    /// None for inner transactions
+    pub id: Option<TxId>,
+    pub sender: Address,
+    /// None for inner transactions (the indexer only sets it on top level transactions: use the parent's)
+    pub round: Option<u64>,
+    /// None for inner transactions, like [Self::round]
+    pub timestamp: Option<Timestamp>,
+    /// Group id (base64), if the tx is part of a group
+    pub group: Option<String>,
+    pub kind: IndexedTxKind,
+    pub inner_txs: Vec<IndexedTx>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexedTxKind {
+    AppCall {
+        app_id: u64,
+        args: Vec<Vec<u8>>,
+    },
+    AssetTransfer {
+        asset_id: u64,
+        amount: u64,
+        receiver: Address,
+    },
+    /// Transaction types we don't process
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedTxPage {
+    pub txs: Vec<IndexedTx>,
+    /// Pass to the next query to get the next page. None if there are no more txs.
+    pub next_token: Option<String>,
+}
+
+/// The indexer queries needed to read historical state.
+/// Implemented by [Indexer] and, for tests, [super::fake_indexer_client::FakeIndexerClient].
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait IndexerClient {
+    async fn current_round(&self) -> Result<u64>;
+    async fn block_timestamp(&self, round: u64) -> Result<Timestamp>;
+    /// Calls to the app (including its creation and calls made by inner transactions) up to `max_round`,
+    /// in confirmation order.
+    async fn app_calls(
+        &self,
+        app_id: u64,
+        max_round: u64,
+        next_token: Option<String>,
+    ) -> Result<AppCallDeltasPage>;
+    /// Transactions where the address is sender or receiver (including inner transactions),
+    /// at most `limit` per page.
+    async fn txs_with_address(
+        &self,
+        address: &Address,
+        next_token: Option<String>,
+        limit: u64,
+    ) -> Result<IndexedTxPage>;
+    /// Top level transactions of the group, which was confirmed in `round`
+    async fn group_txs(&self, round: u64, group: &str) -> Result<Vec<IndexedTx>>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IndexerClient for Indexer {
+    async fn current_round(&self) -> Result<u64> {
+        Ok(self.health().await?.round)
+    }
+
+    async fn block_timestamp(&self, round: u64) -> Result<Timestamp> {
+        Ok(Timestamp(self.block(Round(round)).await?.timestamp))
+    }
+
+    async fn app_calls(
+        &self,
+        app_id: u64,
+        max_round: u64,
+        next_token: Option<String>,
+    ) -> Result<AppCallDeltasPage> {
+        let res = self
+            .transactions(&QueryTransaction {
+                application_id: Some(app_id),
+                max_round: Some(max_round),
+                limit: Some(APP_CALLS_PAGE_SIZE),
+                next: next_token,
+                ..QueryTransaction::default()
+            })
+            .await?;
+
+        let mut calls = vec![];
+        for tx in &res.transactions {
+            collect_app_calls(tx, app_id, &mut calls)?;
+        }
+        Ok(AppCallDeltasPage {
+            calls,
+            next_token: res.next_token,
+        })
+    }
+
+    async fn txs_with_address(
+        &self,
+        address: &Address,
+        next_token: Option<String>,
+        limit: u64,
+    ) -> Result<IndexedTxPage> {
+        let res = self
+            .transactions(&QueryTransaction {
+                address: Some(address.to_string()),
+                limit: Some(limit),
+                next: next_token,
+                ..QueryTransaction::default()
+            })
+            .await?;
+
+        Ok(IndexedTxPage {
+            txs: res
+                .transactions
+                .iter()
+                .map(to_indexed_tx)
+                .collect::<Result<_>>()?,
+            next_token: res.next_token,
+        })
+    }
+
+    async fn group_txs(&self, round: u64, group: &str) -> Result<Vec<IndexedTx>> {
+        // the indexer can't filter by group, so we get the round's transactions
+        let mut txs = vec![];
+        let mut next_token = None;
+        loop {
+            let res = self
+                .transactions(&QueryTransaction {
+                    min_round: Some(round),
+                    max_round: Some(round),
+                    next: next_token,
+                    ..QueryTransaction::default()
+                })
+                .await?;
+            for tx in &res.transactions {
+                if tx.group.as_deref() == Some(group) {
+                    txs.push(to_indexed_tx(tx)?);
+                }
+            }
+            match res.next_token {
+                Some(token) if !res.transactions.is_empty() => next_token = Some(token),
+                _ => return Ok(txs),
+            }
+        }
+    }
+}
+
+/// Global state as of `round`, rebuilt by replaying the state deltas of the app calls up to the round.
+/// Note that the indexer's account lookups at a round (`round=`) can't be used for this:
+/// they don't roll back app state, and newer indexers don't support them.
+/// None if the app didn't exist at the round (not created yet or deleted).
+pub async fn app_state_at_round(
+    indexer: &impl IndexerClient,
+    app_id: u64,
+    round: u64,
+) -> Result<Option<HistoricalAppState>> {
+    replay_global_state(&app_calls_until_round(indexer, app_id, round).await?)
+}
+
+/// Local state of the account as of `round`, rebuilt like [app_state_at_round].
+/// None if the account wasn't opted in to the app at the round.
+pub async fn local_state_at_round(
+    indexer: &impl IndexerClient,
+    address: &Address,
+    app_id: u64,
+    round: u64,
+) -> Result<Option<Vec<TealKeyValue>>> {
+    Ok(replay_local_state(
+        &app_calls_until_round(indexer, app_id, round).await?,
+        address,
+    ))
+}
+
+/// All the calls to the app up to `round` (fetches all the pages)
+pub async fn app_calls_until_round(
+    indexer: &impl IndexerClient,
+    app_id: u64,
+    round: u64,
+) -> Result<Vec<AppCallDeltas>> {
+    let mut calls = vec![];
+    let mut next_token = None;
+    loop {
+        let page = indexer.app_calls(app_id, round, next_token).await?;
+        calls.extend(page.calls);
+        match page.next_token {
+            Some(token) => next_token = Some(token),
+            None => return Ok(calls),
+        }
+    }
+}
+
+pub fn replay_global_state(calls: &[AppCallDeltas]) -> Result<Option<HistoricalAppState>> {
+    let mut state: Option<(Address, BTreeMap<String, TealValue>)> = None;
+    for call in calls {
+        if call.creates_app {
+            state = Some((call.sender, BTreeMap::new()));
+        }
+        let (_, key_values) = state.as_mut().ok_or_else(|| {
+            anyhow!(
+                "Unexpected: app call in round: {} before the app creation",
+                call.round
+            )
+        })?;
+        apply_deltas(key_values, &call.global_delta);
+        if call.completion == AppCallCompletion::Delete {
+            state = None;
+        }
+    }
+    Ok(state.map(|(creator, key_values)| HistoricalAppState {
+        creator,
+        global_state: to_key_values(key_values),
+    }))
+}
+
+pub fn replay_local_state(calls: &[AppCallDeltas], address: &Address) -> Option<Vec<TealKeyValue>> {
+    let mut state: Option<BTreeMap<String, TealValue>> = None;
+    for call in calls {
+        let is_sender = &call.sender == address;
+        if is_sender && call.completion == AppCallCompletion::OptIn {
+            state = Some(BTreeMap::new());
+        }
+        if let Some(key_values) = state.as_mut() {
+            for (_, deltas) in call
+                .local_deltas
+                .iter()
+                .filter(|(delta_address, _)| delta_address == address)
+            {
+                apply_deltas(key_values, deltas);
+            }
+        }
+        if is_sender
+            && matches!(
+                call.completion,
+                AppCallCompletion::CloseOut | AppCallCompletion::ClearState
+            )
+        {
+            state = None;
+        }
+    }
+    state.map(to_key_values)
+}
+
+fn apply_deltas(key_values: &mut BTreeMap<String, TealValue>, deltas: &[StateDelta]) {
+    for delta in deltas {
+        match &delta.action {
+            StateDeltaAction::SetBytes(bytes) => {
+                key_values.insert(
+                    delta.key.clone(),
+                    TealValue {
+                        bytes: bytes.clone(),
+                        uint: 0,
+                        value_type: TEAL_VALUE_TYPE_BYTES,
+                    },
+                );
+            }
+            StateDeltaAction::SetUint(uint) => {
+                key_values.insert(
+                    delta.key.clone(),
+                    TealValue {
+                        bytes: vec![],
+                        uint: *uint,
+                        value_type: TEAL_VALUE_TYPE_UINT,
+                    },
+                );
+            }
+            StateDeltaAction::Delete => {
+                key_values.remove(&delta.key);
+            }
+        }
+    }
+}
+
+fn to_key_values(key_values: BTreeMap<String, TealValue>) -> Vec<TealKeyValue> {
+    key_values
+        .into_iter()
+        .map(|(key, value)| TealKeyValue { key, value })
+        .collect()
+}
+
+/// Adds the calls to the app in the (top level) tx and its inner txs, in execution order
+fn collect_app_calls(tx: &Transaction, app_id: u64, calls: &mut Vec<AppCallDeltas>) -> Result<()> {
+    let round = confirmed_round(tx)?;
+    collect_app_calls_in_round(tx, app_id, round, calls)
+}
+
+/// Inner txs don't have a round: they get the one of the top level tx
+fn collect_app_calls_in_round(
+    tx: &Transaction,
+    app_id: u64,
+    round: u64,
+    calls: &mut Vec<AppCallDeltas>,
+) -> Result<()> {
+    if let Some(app_call) = &tx.application_transaction {
+        let creates_app = tx.created_application_index == Some(app_id);
+        if app_call.application_id == app_id || creates_app {
+            calls.push(AppCallDeltas {
+                round,
+                sender: tx.sender.parse().map_err(|e| anyhow!("{e}"))?,
+                completion: to_completion(&app_call.on_completion)?,
+                creates_app,
+                global_delta: tx
+                    .global_state_delta
+                    .iter()
+                    .flatten()
+                    .map(to_state_delta)
+                    .collect::<Result<_>>()?,
+                local_deltas: tx
+                    .local_state_delta
+                    .iter()
+                    .flatten()
+                    .map(|account_delta| {
+                        Ok((
+                            account_delta.address.parse().map_err(|e| anyhow!("{e}"))?,
+                            account_delta
+                                .delta
+                                .iter()
+                                .map(to_state_delta)
+                                .collect::<Result<_>>()?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            });
+        }
+    }
+    for inner in tx.inner_txns.iter().flatten() {
+        collect_app_calls_in_round(inner, app_id, round, calls)?;
+    }
+    Ok(())
+}
+
+/// Errors if missing: we only query confirmed txs, so it's always set on top level txs
+fn confirmed_round(tx: &Transaction) -> Result<u64> {
+    tx.confirmed_round.ok_or_else(|| {
+        anyhow!(
+            "Unexpected: indexer tx without confirmed round: {:?}",
+            tx.id
+        )
+    })
+}
+
+fn to_completion(on_completion: &str) -> Result<AppCallCompletion> {
+    Ok(match on_completion {
+        "noop" => AppCallCompletion::NoOp,
+        "optin" => AppCallCompletion::OptIn,
+        "closeout" => AppCallCompletion::CloseOut,
+        "clear" => AppCallCompletion::ClearState,
+        "update" => AppCallCompletion::Update,
+        "delete" => AppCallCompletion::Delete,
+        _ => return Err(anyhow!("Unknown on completion: {on_completion}")),
+    })
+}
+
+/// See https://developer.algorand.org/docs/rest-apis/indexer/#evaldelta
+fn to_state_delta(kv: &EvalDeltaKeyValue) -> Result<StateDelta> {
+    let action = match kv.value.action {
+        1 => StateDeltaAction::SetBytes(kv.value.bytes.clone().unwrap_or_default()),
+        2 => StateDeltaAction::SetUint(kv.value.uint.unwrap_or_default()),
+        3 => StateDeltaAction::Delete,
+        action => return Err(anyhow!("Unknown state delta action: {action}")),
+    };
+    Ok(StateDelta {
+        key: kv.key.clone(),
+        action,
+    })
+}
+
+/// For top level txs (errors if they don't have a confirmed round)
+fn to_indexed_tx(tx: &Transaction) -> Result<IndexedTx> {
+    confirmed_round(tx)?;
+    to_indexed_tx_with_inner(tx)
+}
+
+fn to_indexed_tx_with_inner(tx: &Transaction) -> Result<IndexedTx> {
+    let kind = match (&tx.application_transaction, &tx.asset_transfer_transaction) {
+        (Some(app_call), _) => IndexedTxKind::AppCall {
+            app_id: app_call.application_id,
+            args: app_call.application_args.clone(),
+        },
+        (_, Some(transfer)) => IndexedTxKind::AssetTransfer {
+            asset_id: transfer.asset_id,
+            amount: transfer.amount,
+            receiver: transfer.receiver.parse().map_err(|e| anyhow!("{e}"))?,
+        },
+        _ => IndexedTxKind::Other,
+    };
+
+    Ok(IndexedTx {
+        id: tx.id.as_ref().map(|id| id.parse()).transpose()?,
+        sender: tx.sender.parse().map_err(|e| anyhow!("{e}"))?,
+        round: tx.confirmed_round,
+        timestamp: tx.round_time.map(Timestamp),
+        group: tx.group.clone(),
+        kind,
+        inner_txs: tx
+            .inner_txns
+            .iter()
+            .flatten()
+            .map(to_indexed_tx_with_inner)
+            .collect::<Result<_>>()?,
+    })
+}
@@ -0,0 +1,4 @@
+pub mod chain_client;
+pub mod fake_chain_client;
+pub mod fake_indexer_client;
+pub mod indexer_client;
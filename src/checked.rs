@@ -1,21 +1,97 @@
-use anyhow::Result;
+use std::fmt::{self, Display, Formatter};
 
 pub trait CheckedAdd: Sized {
-    fn add(&self, o: &Self) -> Result<Self>;
+    fn add(&self, o: &Self) -> Result<Self, ArithmeticError>;
 }
 
 pub trait CheckedSub: Sized {
-    fn sub(&self, o: &Self) -> Result<Self>;
+    fn sub(&self, o: &Self) -> Result<Self, ArithmeticError>;
 }
 
 pub trait CheckedMul: Sized {
-    fn mul(&self, o: &Self) -> Result<Self>;
+    fn mul(&self, o: &Self) -> Result<Self, ArithmeticError>;
 }
 
 pub trait CheckedDiv: Sized {
-    fn div(&self, o: &Self) -> Result<Self>;
+    fn div(&self, o: &Self) -> Result<Self, ArithmeticError>;
 }
 
 pub trait CheckedMulOther<Rhs = Self>: Sized {
-    fn mul(self, rhs: Rhs) -> Result<Self>;
+    fn mul(self, rhs: Rhs) -> Result<Self, ArithmeticError>;
 }
+
+pub trait SaturatingAdd {
+    fn saturating_add(&self, o: &Self) -> Self;
+}
+
+pub trait SaturatingSub {
+    fn saturating_sub(&self, o: &Self) -> Self;
+}
+
+pub trait SaturatingMul {
+    fn saturating_mul(&self, o: &Self) -> Self;
+}
+
+/// Checked counterpart of [std::iter::Sum]: fails on the first overflow.
+/// The fold starts at Default, which is expected to be 0.
+pub trait CheckedSum<T>: Iterator<Item = T> + Sized {
+    fn checked_sum(self) -> Result<T, ArithmeticError>;
+}
+
+impl<T, I> CheckedSum<T> for I
+where
+    T: CheckedAdd + Default,
+    I: Iterator<Item = T>,
+{
+    fn checked_sum(mut self) -> Result<T, ArithmeticError> {
+        self.try_fold(T::default(), |acc, v| acc.add(&v))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Display for ArithmeticOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            ArithmeticOp::Add => "+",
+            ArithmeticOp::Sub => "-",
+            ArithmeticOp::Mul => "*",
+            ArithmeticOp::Div => "/",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// A failed checked operation, with its operands (in base units)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArithmeticError {
+    pub op: ArithmeticOp,
+    pub lhs: u64,
+    pub rhs: u64,
+}
+
+impl ArithmeticError {
+    pub fn new(op: ArithmeticOp, lhs: u64, rhs: u64) -> ArithmeticError {
+        ArithmeticError { op, lhs, rhs }
+    }
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let ArithmeticError { op, lhs, rhs } = self;
+        let reason = match op {
+            ArithmeticOp::Sub => "underflow",
+            ArithmeticOp::Div => "division by zero",
+            ArithmeticOp::Add | ArithmeticOp::Mul => "overflow",
+        };
+        write!(f, "Failed: {lhs} {op} {rhs} ({reason})")
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
@@ -1,4 +1,6 @@
 pub mod api;
+pub mod calc;
+pub mod chain;
 pub mod checked;
 pub mod date_util;
 pub mod dependencies;
@@ -6,4 +8,6 @@ pub mod logger;
 pub mod models;
 pub mod state;
 pub mod teal;
+#[cfg(test)]
+mod test_util;
 pub mod util;
@@ -1,13 +1,15 @@
-use crate::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedMulOther, CheckedSub};
+use crate::checked::{
+    ArithmeticError, ArithmeticOp, CheckedAdd, CheckedDiv, CheckedMul, CheckedMulOther, CheckedSub,
+    SaturatingAdd, SaturatingMul, SaturatingSub,
+};
 use crate::util::decimal_util::AsDecimal;
-use anyhow::{anyhow, Result};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialOrd;
 use std::fmt::Display;
 
 /// An amount of assets (ASA)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord, Default)]
 pub struct AssetAmount(pub u64);
 
 impl AssetAmount {
@@ -39,41 +41,120 @@ impl PartialOrd<u64> for AssetAmount {
 }
 
 impl CheckedAdd for AssetAmount {
-    fn add(&self, v: &Self) -> Result<Self> {
-        Ok(AssetAmount(self.0.checked_add(v.0).ok_or_else(|| {
-            anyhow!("Failed: {self} + {v}".to_owned())
-        })?))
+    fn add(&self, v: &Self) -> Result<Self, ArithmeticError> {
+        checked(self.0.checked_add(v.0), ArithmeticOp::Add, self.0, v.0)
     }
 }
 
 impl CheckedSub for AssetAmount {
-    fn sub(&self, v: &Self) -> Result<Self> {
-        Ok(AssetAmount(self.0.checked_sub(v.0).ok_or_else(|| {
-            anyhow!("Failed: {self} - {v}".to_owned())
-        })?))
+    fn sub(&self, v: &Self) -> Result<Self, ArithmeticError> {
+        checked(self.0.checked_sub(v.0), ArithmeticOp::Sub, self.0, v.0)
     }
 }
 
 impl CheckedMul for AssetAmount {
-    fn mul(&self, v: &Self) -> Result<Self> {
-        Ok(AssetAmount(self.0.checked_mul(v.0).ok_or_else(|| {
-            anyhow!("Failed: {self} * {v}".to_owned())
-        })?))
+    fn mul(&self, v: &Self) -> Result<Self, ArithmeticError> {
+        checked(self.0.checked_mul(v.0), ArithmeticOp::Mul, self.0, v.0)
     }
 }
 
 impl CheckedDiv for AssetAmount {
-    fn div(&self, v: &Self) -> Result<Self> {
-        Ok(AssetAmount(self.0.checked_div(v.0).ok_or_else(|| {
-            anyhow!("Failed: {self} / {v}".to_owned())
-        })?))
+    fn div(&self, v: &Self) -> Result<Self, ArithmeticError> {
+        checked(self.0.checked_div(v.0), ArithmeticOp::Div, self.0, v.0)
     }
 }
 
 impl CheckedMulOther<u64> for AssetAmount {
-    fn mul(self, rhs: u64) -> Result<Self> {
-        Ok(AssetAmount(self.0.checked_mul(rhs).ok_or_else(|| {
-            anyhow!("Failed: {self} * {v}".to_owned())
-        })?))
+    fn mul(self, rhs: u64) -> Result<Self, ArithmeticError> {
+        checked(self.0.checked_mul(rhs), ArithmeticOp::Mul, self.0, rhs)
+    }
+}
+
+impl SaturatingAdd for AssetAmount {
+    fn saturating_add(&self, v: &Self) -> Self {
+        AssetAmount(self.0.saturating_add(v.0))
+    }
+}
+
+impl SaturatingSub for AssetAmount {
+    fn saturating_sub(&self, v: &Self) -> Self {
+        AssetAmount(self.0.saturating_sub(v.0))
+    }
+}
+
+impl SaturatingMul for AssetAmount {
+    fn saturating_mul(&self, v: &Self) -> Self {
+        AssetAmount(self.0.saturating_mul(v.0))
+    }
+}
+
+fn checked(
+    res: Option<u64>,
+    op: ArithmeticOp,
+    lhs: u64,
+    rhs: u64,
+) -> Result<AssetAmount, ArithmeticError> {
+    res.map(AssetAmount)
+        .ok_or_else(|| ArithmeticError::new(op, lhs, rhs))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        checked::{
+            ArithmeticError, ArithmeticOp, CheckedAdd, CheckedDiv, CheckedMulOther, CheckedSub,
+            CheckedSum, SaturatingAdd, SaturatingSub,
+        },
+        models::{funds::FundsAmount, share_amount::ShareAmount},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_errors_carry_operands() {
+        assert_eq!(
+            Err(ArithmeticError::new(ArithmeticOp::Add, u64::MAX, 1)),
+            FundsAmount::new(u64::MAX).add(&FundsAmount::new(1))
+        );
+        assert_eq!(
+            Err(ArithmeticError::new(ArithmeticOp::Sub, 1, 2)),
+            ShareAmount::new(1).sub(&ShareAmount::new(2))
+        );
+        assert_eq!(
+            Err(ArithmeticError::new(ArithmeticOp::Div, 1, 0)),
+            ShareAmount::new(1).div(&ShareAmount::new(0))
+        );
+        let mul_err = FundsAmount::new(u64::MAX).mul(2).unwrap_err();
+        assert_eq!(
+            ArithmeticError::new(ArithmeticOp::Mul, u64::MAX, 2),
+            mul_err
+        );
+        assert_eq!(
+            "Failed: 18446744073709551615 * 2 (overflow)",
+            mul_err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_checked_sum() -> Result<()> {
+        let amounts = [1, 2, 3].iter().map(|a| FundsAmount::new(*a));
+        assert_eq!(FundsAmount::new(6), amounts.checked_sum()?);
+        assert_eq!(ShareAmount::new(0), std::iter::empty().checked_sum()?);
+
+        let overflowing = [u64::MAX, 1].iter().map(|a| ShareAmount::new(*a));
+        assert!(overflowing.checked_sum().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_saturating_and_ordering() {
+        let max = FundsAmount::new(u64::MAX);
+        assert_eq!(max, max.saturating_add(&FundsAmount::new(1)));
+        assert_eq!(
+            ShareAmount::new(0),
+            ShareAmount::new(1).saturating_sub(&ShareAmount::new(2))
+        );
+        assert!(FundsAmount::new(1) < FundsAmount::new(2));
+        let shares = [1, 3, 2].iter().map(|a| ShareAmount::new(*a));
+        assert_eq!(Some(ShareAmount::new(3)), shares.max());
     }
 }
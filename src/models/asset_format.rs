@@ -0,0 +1,336 @@
+use super::{
+    funds::{FundsAmount, FundsAssetId},
+    share_amount::ShareAmount,
+};
+use anyhow::{anyhow, Result};
+use std::{collections::HashMap, convert::TryFrom};
+
+/// The assets we format: the funds asset and the shares of a DAO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKey {
+    Funds(FundsAssetId),
+    Shares(u64),
+}
+
+/// The ASA params relevant for display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetMetadata {
+    pub decimals: u32,
+    pub unit_name: String,
+}
+
+/// Converts between base units and user facing amounts (e.g. 12500000 <-> "12.50 USDC").
+/// Formatting and parsing are locale independent: "." is always the decimal separator and there are no group separators.
+#[derive(Debug, Clone, Default)]
+pub struct AssetFormatter {
+    assets: HashMap<AssetKey, AssetMetadata>,
+}
+
+/// Fraction digits shown at least when formatting, if the asset has them (so e.g. 12.5 is shown as 12.50)
+const MIN_DISPLAY_DECIMALS: u32 = 2;
+
+/// u64::MAX has 20 digits, so 10^19 is the largest power of 10 in u64
+const MAX_DECIMALS: u32 = 19;
+
+impl AssetFormatter {
+    pub fn new() -> AssetFormatter {
+        AssetFormatter::default()
+    }
+
+    pub fn add_funds_asset(&mut self, id: FundsAssetId, metadata: AssetMetadata) -> Result<()> {
+        self.add(AssetKey::Funds(id), metadata)
+    }
+
+    pub fn add_shares_asset(&mut self, id: u64, metadata: AssetMetadata) -> Result<()> {
+        self.add(AssetKey::Shares(id), metadata)
+    }
+
+    fn add(&mut self, key: AssetKey, metadata: AssetMetadata) -> Result<()> {
+        if metadata.decimals > MAX_DECIMALS {
+            return Err(anyhow!(
+                "Asset: {key:?} has {} decimals. Max supported: {MAX_DECIMALS}",
+                metadata.decimals
+            ));
+        }
+        self.assets.insert(key, metadata);
+        Ok(())
+    }
+
+    pub fn metadata(&self, key: AssetKey) -> Result<&AssetMetadata> {
+        self.assets
+            .get(&key)
+            .ok_or_else(|| anyhow!("No metadata for asset: {key:?}"))
+    }
+
+    /// Exact amount with unit, e.g. "12.50 USDC"
+    pub fn format_funds(&self, id: FundsAssetId, amount: FundsAmount) -> Result<String> {
+        self.format(AssetKey::Funds(id), amount.val(), None)
+    }
+
+    /// Amount rounded (half to even) to `dp` fraction digits, with unit
+    pub fn format_funds_dp(
+        &self,
+        id: FundsAssetId,
+        amount: FundsAmount,
+        dp: u32,
+    ) -> Result<String> {
+        self.format(AssetKey::Funds(id), amount.val(), Some(dp))
+    }
+
+    pub fn format_shares(&self, id: u64, amount: ShareAmount) -> Result<String> {
+        self.format(AssetKey::Shares(id), amount.val(), None)
+    }
+
+    pub fn format_shares_dp(&self, id: u64, amount: ShareAmount, dp: u32) -> Result<String> {
+        self.format(AssetKey::Shares(id), amount.val(), Some(dp))
+    }
+
+    /// Parses user input (e.g. "12.5", optionally followed by the unit name) into base units.
+    /// Fails if the input has more fraction digits than the asset supports.
+    pub fn parse_funds(&self, id: FundsAssetId, input: &str) -> Result<FundsAmount> {
+        Ok(FundsAmount::new(self.parse(AssetKey::Funds(id), input)?))
+    }
+
+    pub fn parse_shares(&self, id: u64, input: &str) -> Result<ShareAmount> {
+        Ok(ShareAmount::new(self.parse(AssetKey::Shares(id), input)?))
+    }
+
+    fn format(&self, key: AssetKey, base_units: u64, dp: Option<u32>) -> Result<String> {
+        let metadata = self.metadata(key)?;
+        let number = match dp {
+            Some(dp) => format_rounded(base_units, metadata.decimals, dp),
+            None => format_exact(base_units, metadata.decimals),
+        };
+        Ok(format!("{number} {}", metadata.unit_name))
+    }
+
+    fn parse(&self, key: AssetKey, input: &str) -> Result<u64> {
+        let metadata = self.metadata(key)?;
+        let input = input.trim();
+        let number = input
+            .strip_suffix(metadata.unit_name.as_str())
+            .filter(|_| !metadata.unit_name.is_empty())
+            .unwrap_or(input)
+            .trim_end();
+        parse_base_units(number, metadata.decimals)
+    }
+}
+
+/// All the fraction digits, with trailing zeros removed down to [MIN_DISPLAY_DECIMALS]
+fn format_exact(base_units: u64, decimals: u32) -> String {
+    let (int, fract) = split(base_units as u128, decimals);
+    let mut fract = fract.trim_end_matches('0').to_owned();
+    let min_len = MIN_DISPLAY_DECIMALS.min(decimals) as usize;
+    while fract.len() < min_len {
+        fract.push('0');
+    }
+    join(int, &fract)
+}
+
+fn format_rounded(base_units: u64, decimals: u32, dp: u32) -> String {
+    if dp >= decimals {
+        let (int, fract) = split(base_units as u128, decimals);
+        let padding = "0".repeat((dp - decimals) as usize);
+        return join(int, &format!("{fract}{padding}"));
+    }
+    let divisor = 10u128.pow(decimals - dp);
+    let value = base_units as u128;
+    let quotient = value / divisor;
+    let remainder = value % divisor;
+    let rounded = match (remainder * 2).cmp(&divisor) {
+        std::cmp::Ordering::Less => quotient,
+        std::cmp::Ordering::Greater => quotient + 1,
+        std::cmp::Ordering::Equal => quotient + quotient % 2,
+    };
+    let (int, fract) = split(rounded, dp);
+    join(int, &fract)
+}
+
+/// Integer part and zero-padded fraction digits
+fn split(value: u128, decimals: u32) -> (u128, String) {
+    let unit = 10u128.pow(decimals);
+    let fract = if decimals == 0 {
+        String::new()
+    } else {
+        format!("{:0width$}", value % unit, width = decimals as usize)
+    };
+    (value / unit, fract)
+}
+
+fn join(int: u128, fract: &str) -> String {
+    if fract.is_empty() {
+        int.to_string()
+    } else {
+        format!("{int}.{fract}")
+    }
+}
+
+fn parse_base_units(number: &str, decimals: u32) -> Result<u64> {
+    let (int, fract) = match number.split_once('.') {
+        Some((int, fract)) => (int, fract),
+        None => (number, ""),
+    };
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (int.is_empty() && fract.is_empty()) || !is_digits(int) || !is_digits(fract) {
+        return Err(anyhow!("Invalid amount: {number:?}"));
+    }
+
+    let fract = fract.trim_end_matches('0');
+    if fract.len() > decimals as usize {
+        return Err(anyhow!(
+            "Amount: {number} has more than {decimals} fraction digits"
+        ));
+    }
+
+    let int_units = if int.is_empty() {
+        0
+    } else {
+        int.parse::<u128>()
+            .map_err(|_| anyhow!("Amount too large: {number}"))?
+    };
+    let fract_units = format!("{fract:0<width$}", width = decimals as usize);
+    let fract_units = if fract_units.is_empty() {
+        0
+    } else {
+        fract_units.parse::<u128>()?
+    };
+
+    int_units
+        .checked_mul(10u128.pow(decimals))
+        .and_then(|units| units.checked_add(fract_units))
+        .and_then(|units| u64::try_from(units).ok())
+        .ok_or_else(|| anyhow!("Amount too large: {number}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        asset_format::{AssetFormatter, AssetMetadata},
+        funds::{FundsAmount, FundsAssetId},
+        share_amount::ShareAmount,
+    };
+    use anyhow::Result;
+
+    const FUNDS_ID: FundsAssetId = FundsAssetId(123);
+    const SHARES_ID: u64 = 456;
+
+    fn formatter() -> Result<AssetFormatter> {
+        let mut formatter = AssetFormatter::new();
+        formatter.add_funds_asset(
+            FUNDS_ID,
+            AssetMetadata {
+                decimals: 6,
+                unit_name: "USDC".to_owned(),
+            },
+        )?;
+        formatter.add_shares_asset(
+            SHARES_ID,
+            AssetMetadata {
+                decimals: 0,
+                unit_name: "SHR".to_owned(),
+            },
+        )?;
+        Ok(formatter)
+    }
+
+    #[test]
+    fn test_format() -> Result<()> {
+        let f = formatter()?;
+
+        assert_eq!(
+            "12.50 USDC",
+            f.format_funds(FUNDS_ID, FundsAmount::new(12_500_000))?
+        );
+        assert_eq!(
+            "0.000001 USDC",
+            f.format_funds(FUNDS_ID, FundsAmount::new(1))?
+        );
+        assert_eq!("0.00 USDC", f.format_funds(FUNDS_ID, FundsAmount::new(0))?);
+        assert_eq!(
+            "18446744073709.551615 USDC",
+            f.format_funds(FUNDS_ID, FundsAmount::new(u64::MAX))?
+        );
+        assert_eq!(
+            "100 SHR",
+            f.format_shares(SHARES_ID, ShareAmount::new(100))?
+        );
+
+        // half to even
+        assert_eq!(
+            "0.12 USDC",
+            f.format_funds_dp(FUNDS_ID, FundsAmount::new(125_000), 2)?
+        );
+        assert_eq!(
+            "0.14 USDC",
+            f.format_funds_dp(FUNDS_ID, FundsAmount::new(135_000), 2)?
+        );
+        assert_eq!(
+            "0.14 USDC",
+            f.format_funds_dp(FUNDS_ID, FundsAmount::new(135_001), 2)?
+        );
+        assert_eq!(
+            "1 USDC",
+            f.format_funds_dp(FUNDS_ID, FundsAmount::new(999_999), 0)?
+        );
+        assert_eq!(
+            "0.00000100 USDC",
+            f.format_funds_dp(FUNDS_ID, FundsAmount::new(1), 8)?
+        );
+
+        assert!(f
+            .format_funds(FundsAssetId(1), FundsAmount::new(1))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let f = formatter()?;
+
+        assert_eq!(
+            FundsAmount::new(12_500_000),
+            f.parse_funds(FUNDS_ID, "12.5")?
+        );
+        assert_eq!(
+            FundsAmount::new(12_500_000),
+            f.parse_funds(FUNDS_ID, " 12.50 USDC ")?
+        );
+        assert_eq!(FundsAmount::new(500_000), f.parse_funds(FUNDS_ID, ".5")?);
+        assert_eq!(
+            FundsAmount::new(12_000_000),
+            f.parse_funds(FUNDS_ID, "12.")?
+        );
+        assert_eq!(FundsAmount::new(1), f.parse_funds(FUNDS_ID, "0.0000010")?);
+        assert_eq!(
+            FundsAmount::new(u64::MAX),
+            f.parse_funds(FUNDS_ID, "18446744073709.551615")?
+        );
+        assert_eq!(ShareAmount::new(100), f.parse_shares(SHARES_ID, "100")?);
+
+        // excess precision
+        assert!(f.parse_funds(FUNDS_ID, "0.0000001").is_err());
+        assert!(f.parse_shares(SHARES_ID, "1.5").is_err());
+        // overflow
+        assert!(f.parse_funds(FUNDS_ID, "18446744073709.551616").is_err());
+        // not locale formatted / not a plain amount
+        for invalid in [
+            "", ".", "1,5", "1 000", "-1", "+1", "1e3", "1.2.3", "12 EUR",
+        ] {
+            assert!(f.parse_funds(FUNDS_ID, invalid).is_err(), "{:?}", invalid);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_parse_roundtrip() -> Result<()> {
+        let f = formatter()?;
+        for amount in [0, 1, 10, 999_999, 1_000_000, 12_345_678, u64::MAX] {
+            let formatted = f.format_funds(FUNDS_ID, FundsAmount::new(amount))?;
+            assert_eq!(
+                FundsAmount::new(amount),
+                f.parse_funds(FUNDS_ID, &formatted)?
+            );
+        }
+        Ok(())
+    }
+}
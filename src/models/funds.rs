@@ -1,14 +1,16 @@
 use super::asset_amount::AssetAmount;
 use crate::{
-    checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedMulOther, CheckedSub},
+    checked::{
+        ArithmeticError, CheckedAdd, CheckedDiv, CheckedMul, CheckedMulOther, CheckedSub,
+        SaturatingAdd, SaturatingMul, SaturatingSub,
+    },
     util::decimal_util::AsDecimal,
 };
-use anyhow::Result;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct FundsAmount(pub AssetAmount);
 
 impl Display for FundsAmount {
@@ -36,19 +38,19 @@ impl FundsAmount {
 }
 
 impl CheckedAdd for FundsAmount {
-    fn add(&self, o: &Self) -> Result<Self> {
+    fn add(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(FundsAmount(self.0.add(&o.0)?))
     }
 }
 
 impl CheckedSub for FundsAmount {
-    fn sub(&self, o: &Self) -> Result<Self> {
+    fn sub(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(FundsAmount(self.0.sub(&o.0)?))
     }
 }
 
 impl CheckedMul for FundsAmount {
-    fn mul(&self, o: &Self) -> Result<Self> {
+    fn mul(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(FundsAmount(<AssetAmount as CheckedMul>::mul(
             &self.0, &o.0,
         )?))
@@ -56,18 +58,36 @@ impl CheckedMul for FundsAmount {
 }
 
 impl CheckedDiv for FundsAmount {
-    fn div(&self, o: &Self) -> Result<Self> {
+    fn div(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(FundsAmount(self.0.div(&o.0)?))
     }
 }
 
 impl CheckedMulOther<u64> for FundsAmount {
-    fn mul(self, rhs: u64) -> Result<Self> {
+    fn mul(self, rhs: u64) -> Result<Self, ArithmeticError> {
         Ok(FundsAmount(self.0.mul(rhs)?))
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl SaturatingAdd for FundsAmount {
+    fn saturating_add(&self, o: &Self) -> Self {
+        FundsAmount(self.0.saturating_add(&o.0))
+    }
+}
+
+impl SaturatingSub for FundsAmount {
+    fn saturating_sub(&self, o: &Self) -> Self {
+        FundsAmount(self.0.saturating_sub(&o.0))
+    }
+}
+
+impl SaturatingMul for FundsAmount {
+    fn saturating_mul(&self, o: &Self) -> Self {
+        FundsAmount(self.0.saturating_mul(&o.0))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct FundsAssetId(pub u64);
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -11,3 +11,4 @@ pub mod tx_id;
 pub mod setup_dao_specs;
 pub mod create_shares_specs;
 pub mod hashable;
+pub mod asset_format;
@@ -1,14 +1,14 @@
-use crate::checked::{CheckedAdd, CheckedMulOther, CheckedSub};
-
 use super::asset_amount::AssetAmount;
-use anyhow::Result;
+use crate::checked::{
+    ArithmeticError, CheckedAdd, CheckedDiv, CheckedMul, CheckedMulOther, CheckedSub,
+    SaturatingAdd, SaturatingMul, SaturatingSub,
+};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
 use std::fmt::Display;
 
 /// An amount of shares (DAO ASA)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub struct ShareAmount(pub AssetAmount);
 
 impl ShareAmount {
@@ -31,12 +31,6 @@ impl Display for ShareAmount {
     }
 }
 
-impl PartialOrd for ShareAmount {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
-    }
-}
-
 impl From<AssetAmount> for ShareAmount {
     fn from(amount: AssetAmount) -> Self {
         ShareAmount(amount)
@@ -44,19 +38,51 @@ impl From<AssetAmount> for ShareAmount {
 }
 
 impl CheckedAdd for ShareAmount {
-    fn add(&self, o: &Self) -> Result<Self> {
+    fn add(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(ShareAmount(self.0.add(&o.0)?))
     }
 }
 
 impl CheckedSub for ShareAmount {
-    fn sub(&self, o: &Self) -> Result<Self> {
+    fn sub(&self, o: &Self) -> Result<Self, ArithmeticError> {
         Ok(ShareAmount(self.0.sub(&o.0)?))
     }
 }
 
+impl CheckedMul for ShareAmount {
+    fn mul(&self, o: &Self) -> Result<Self, ArithmeticError> {
+        Ok(ShareAmount(<AssetAmount as CheckedMul>::mul(
+            &self.0, &o.0,
+        )?))
+    }
+}
+
+impl CheckedDiv for ShareAmount {
+    fn div(&self, o: &Self) -> Result<Self, ArithmeticError> {
+        Ok(ShareAmount(self.0.div(&o.0)?))
+    }
+}
+
 impl CheckedMulOther<u64> for ShareAmount {
-    fn mul(self, rhs: u64) -> Result<Self> {
+    fn mul(self, rhs: u64) -> Result<Self, ArithmeticError> {
         Ok(ShareAmount(self.0.mul(rhs)?))
     }
 }
+
+impl SaturatingAdd for ShareAmount {
+    fn saturating_add(&self, o: &Self) -> Self {
+        ShareAmount(self.0.saturating_add(&o.0))
+    }
+}
+
+impl SaturatingSub for ShareAmount {
+    fn saturating_sub(&self, o: &Self) -> Self {
+        ShareAmount(self.0.saturating_sub(&o.0))
+    }
+}
+
+impl SaturatingMul for ShareAmount {
+    fn saturating_mul(&self, o: &Self) -> Self {
+        ShareAmount(self.0.saturating_mul(&o.0))
+    }
+}
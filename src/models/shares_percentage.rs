@@ -1,8 +1,12 @@
-use crate::util::decimal_util::AsDecimal;
+use super::{funds::FundsAmount, share_amount::ShareAmount};
+use crate::{
+    checked::{ArithmeticError, ArithmeticOp},
+    util::decimal_util::AsDecimal,
+};
 use anyhow::{anyhow, Result};
 use rust_decimal::{prelude::ToPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 
 /// 4 decimals allow us to represent percentages with 2 decimals, e.g. 0.05%
 /// and percentages with 2 decimals is fine for all our purposes
@@ -11,6 +15,9 @@ use std::convert::TryFrom;
 /// the 2nd decimal is just in case
 const MAX_DECIMALS: u32 = 4;
 
+/// Denominator of the integer representation ([SharesPercentage::to_u64]), used in TEAL
+pub const SHARES_PERCENTAGE_PRECISION: u64 = 10u64.pow(MAX_DECIMALS);
+
 // A percentage in range [0..1]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SharesPercentage(Decimal);
@@ -66,13 +73,238 @@ impl TryFrom<u64> for SharesPercentage {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// What TEAL does (integer division)
+    Floor,
+    Ceil,
+    /// Round half to even (banker's rounding)
+    HalfEven,
+}
+
+/// Multiplication / division by a percentage, using its integer encoding ([SharesPercentage::to_u64]),
+/// i.e. `self * percentage / precision` and `self * precision / percentage`, with a single rounding at the end.
+pub trait PercentageMath: Sized {
+    fn mul_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError>;
+    fn div_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError>;
+}
+
+impl PercentageMath for u64 {
+    fn mul_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        mul_div(
+            *self,
+            percentage_units(percentage)?,
+            SHARES_PERCENTAGE_PRECISION,
+            rounding,
+        )
+    }
+
+    fn div_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        mul_div(
+            *self,
+            SHARES_PERCENTAGE_PRECISION,
+            percentage_units(percentage)?,
+            rounding,
+        )
+    }
+}
+
+impl PercentageMath for FundsAmount {
+    fn mul_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        Ok(FundsAmount::new(
+            self.val().mul_percentage(percentage, rounding)?,
+        ))
+    }
+
+    fn div_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        Ok(FundsAmount::new(
+            self.val().div_percentage(percentage, rounding)?,
+        ))
+    }
+}
+
+impl PercentageMath for ShareAmount {
+    fn mul_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        Ok(ShareAmount::new(
+            self.val().mul_percentage(percentage, rounding)?,
+        ))
+    }
+
+    fn div_percentage(
+        &self,
+        percentage: SharesPercentage,
+        rounding: Rounding,
+    ) -> Result<Self, ArithmeticError> {
+        Ok(ShareAmount::new(
+            self.val().div_percentage(percentage, rounding)?,
+        ))
+    }
+}
+
+/// [SharesPercentage::to_u64], which can only fail (overflow of `percentage * precision`)
+/// for percentages not created with the TryFrom impls (e.g. deserialized)
+fn percentage_units(percentage: SharesPercentage) -> Result<u64, ArithmeticError> {
+    percentage.to_u64().map_err(|_| {
+        ArithmeticError::new(
+            ArithmeticOp::Mul,
+            percentage.value().trunc().to_u64().unwrap_or(u64::MAX),
+            SHARES_PERCENTAGE_PRECISION,
+        )
+    })
+}
+
+/// value * mul / div, rounded. The intermediate product is u128, so only the result can overflow,
+/// which is reported as an overflow of `value * mul`.
+fn mul_div(value: u64, mul: u64, div: u64, rounding: Rounding) -> Result<u64, ArithmeticError> {
+    if div == 0 {
+        return Err(ArithmeticError::new(ArithmeticOp::Div, value, div));
+    }
+    let product = value as u128 * mul as u128;
+    let div = div as u128;
+    let quotient = product / div;
+    let remainder = product % div;
+
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => remainder > 0,
+        Rounding::HalfEven => match (remainder * 2).cmp(&div) {
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Equal => quotient % 2 == 1,
+        },
+    };
+    let res = if round_up { quotient + 1 } else { quotient };
+
+    res.try_into()
+        .map_err(|_| ArithmeticError::new(ArithmeticOp::Mul, value, mul))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::models::shares_percentage::SharesPercentage;
+    use crate::{
+        checked::{ArithmeticError, ArithmeticOp},
+        models::{
+            funds::FundsAmount,
+            shares_percentage::{PercentageMath, Rounding, SharesPercentage},
+        },
+    };
     use anyhow::Result;
-    use rust_decimal::Decimal;
+    use rust_decimal::{Decimal, RoundingStrategy};
     use std::convert::TryInto;
 
+    const AMOUNTS: [u64; 10] = [
+        0,
+        1,
+        2,
+        3,
+        5,
+        9_999,
+        10_000,
+        10_001,
+        123_456_789,
+        999_999_999_999,
+    ];
+
+    #[test]
+    fn test_mul_percentage_floor_matches_teal() -> Result<()> {
+        for percentage_u64 in 0..=10_000u64 {
+            let percentage: SharesPercentage = percentage_u64.try_into()?;
+            for amount in AMOUNTS.iter().copied().chain([u64::MAX]) {
+                // the formula in TEAL
+                let expected = (amount as u128 * percentage_u64 as u128 / 10_000) as u64;
+                assert_eq!(
+                    expected,
+                    amount.mul_percentage(percentage, Rounding::Floor)?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_mul_percentage_rounding_modes() -> Result<()> {
+        for percentage_u64 in 0..=10_000u64 {
+            let percentage: SharesPercentage = percentage_u64.try_into()?;
+            for amount in AMOUNTS {
+                let exact = Decimal::from(amount) * percentage.value();
+                assert_eq!(
+                    to_u64(exact.ceil()),
+                    amount.mul_percentage(percentage, Rounding::Ceil)?
+                );
+                assert_eq!(
+                    to_u64(exact.round_dp_with_strategy(0, RoundingStrategy::MidpointNearestEven)),
+                    amount.mul_percentage(percentage, Rounding::HalfEven)?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_div_percentage() -> Result<()> {
+        let percentage: SharesPercentage = Decimal::new(3, 1).try_into()?;
+        let amount = FundsAmount::new(100);
+
+        // 100 / 0.3 = 333.33..
+        assert_eq!(
+            FundsAmount::new(333),
+            amount.div_percentage(percentage, Rounding::Floor)?
+        );
+        assert_eq!(
+            FundsAmount::new(334),
+            amount.div_percentage(percentage, Rounding::Ceil)?
+        );
+        assert_eq!(
+            FundsAmount::new(333),
+            amount.div_percentage(percentage, Rounding::HalfEven)?
+        );
+
+        let zero: SharesPercentage = Decimal::ZERO.try_into()?;
+        assert_eq!(
+            Err(ArithmeticError::new(ArithmeticOp::Div, 100, 0)),
+            amount.div_percentage(zero, Rounding::Floor)
+        );
+        let small: SharesPercentage = Decimal::new(1, 4).try_into()?;
+        assert_eq!(
+            Err(ArithmeticError::new(ArithmeticOp::Mul, u64::MAX, 10_000)),
+            u64::MAX.div_percentage(small, Rounding::Floor)
+        );
+
+        Ok(())
+    }
+
+    fn to_u64(decimal: Decimal) -> u64 {
+        decimal.try_into().unwrap()
+    }
+
     #[test]
     fn test_shares_error_when_created_with_larger_than_1() -> Result<()> {
         let investor_percentage: Decimal = "1.000000001".parse().unwrap();
@@ -1,5 +1,6 @@
+use super::dao_state_error::DaoGlobalStateError;
+use crate::chain::chain_client::ChainClient;
 use algonaut::{
-    algod::v2::Algod,
     core::Address,
     error::ServiceError,
     model::algod::v2::{Account, ApplicationLocalState, TealKeyValue, TealValue},
@@ -11,13 +12,13 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-pub async fn global_state(algod: &Algod, app_id: u64) -> Result<ApplicationGlobalState> {
+pub async fn global_state(algod: &impl ChainClient, app_id: u64) -> Result<ApplicationGlobalState> {
     let app = algod.application_information(app_id).await?;
     Ok(ApplicationGlobalState(app.params.global_state))
 }
 
 pub async fn local_state(
-    algod: &Algod,
+    algod: &impl ChainClient,
     address: &Address,
     app_id: u64,
 ) -> Result<ApplicationLocalState, ApplicationLocalStateError<'static>> {
@@ -48,6 +49,7 @@ pub fn local_state_with_key(
 pub enum ApplicationLocalStateError<'a> {
     NotOptedIn,
     LocalStateNotFound(AppStateKey<'a>),
+    InvalidState(DaoGlobalStateError),
     Msg(String),
 }
 
@@ -61,6 +63,35 @@ impl<'a> AppStateKey<'a> {
     }
 }
 
+/// TealValue type of byte slices
+pub const TEAL_VALUE_TYPE_BYTES: u64 = 1;
+/// TealValue type of uints
+pub const TEAL_VALUE_TYPE_UINT: u64 = 2;
+
+/// Key value for a uint, encoded like the sdk returns it
+pub fn uint_key_value(key: &AppStateKey, value: u64) -> TealKeyValue {
+    TealKeyValue {
+        key: key.to_teal_encoded_str(),
+        value: TealValue {
+            bytes: vec![],
+            uint: value,
+            value_type: TEAL_VALUE_TYPE_UINT,
+        },
+    }
+}
+
+/// Key value for a byte slice, encoded like the sdk returns it
+pub fn bytes_key_value(key: &AppStateKey, value: Vec<u8>) -> TealKeyValue {
+    TealKeyValue {
+        key: key.to_teal_encoded_str(),
+        value: TealValue {
+            bytes: value,
+            uint: 0,
+            value_type: TEAL_VALUE_TYPE_BYTES,
+        },
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// Just a wrapper equivalent to ApplicationLocalState (provided by the SDK), to offer a similar interface
 pub struct ApplicationGlobalState(pub Vec<TealKeyValue>);
@@ -69,6 +100,7 @@ pub trait ApplicationStateExt {
     fn find(&self, key: &AppStateKey) -> Option<TealValue>;
     fn find_uint(&self, key: &AppStateKey) -> Option<u64>;
     fn find_bytes(&self, key: &AppStateKey) -> Option<Vec<u8>>;
+    fn key_values(&self) -> Vec<TealKeyValue>;
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {
         self.len() == 0
@@ -87,6 +119,9 @@ impl ApplicationStateExt for ApplicationLocalState {
     fn find_bytes(&self, key: &AppStateKey) -> Option<Vec<u8>> {
         self.find(key).map(|kv| kv.bytes)
     }
+    fn key_values(&self) -> Vec<TealKeyValue> {
+        self.key_value.clone()
+    }
     fn len(&self) -> usize {
         self.key_value.len()
     }
@@ -104,6 +139,9 @@ impl ApplicationStateExt for ApplicationGlobalState {
     fn find_bytes(&self, key: &AppStateKey) -> Option<Vec<u8>> {
         self.find(key).map(|kv| kv.bytes)
     }
+    fn key_values(&self) -> Vec<TealKeyValue> {
+        self.0.clone()
+    }
     fn len(&self) -> usize {
         self.0.len()
     }
@@ -138,6 +176,15 @@ impl<'a> From<anyhow::Error> for ApplicationLocalStateError<'a> {
     }
 }
 
+impl From<DaoGlobalStateError> for ApplicationLocalStateError<'static> {
+    fn from(e: DaoGlobalStateError) -> Self {
+        match e {
+            DaoGlobalStateError::MissingKey(key, _) => Self::LocalStateNotFound(key),
+            _ => Self::InvalidState(e),
+        }
+    }
+}
+
 impl<'a> From<ServiceError> for ApplicationLocalStateError<'a> {
     fn from(e: ServiceError) -> Self {
         Self::Msg(e.to_string())
@@ -1,79 +1,53 @@
-use super::app_state::{
-    get_uint_value_or_error, local_state, local_state_from_account, AppStateKey,
-    ApplicationGlobalState, ApplicationLocalStateError, ApplicationStateExt,
+use super::{
+    app_state::{
+        bytes_key_value, local_state, local_state_from_account, uint_key_value, AppStateKey,
+        ApplicationGlobalState, ApplicationLocalStateError, ApplicationStateExt,
+        TEAL_VALUE_TYPE_BYTES, TEAL_VALUE_TYPE_UINT,
+    },
+    dao_state_error::{DaoGlobalStateError, StateValueType},
+    dao_state_schema::{
+        schema_keys_in_scope, Presence, StateKeySpec, StateScope, DAO_STATE_SCHEMA,
+        GLOBAL_DAO_DESC, GLOBAL_DAO_NAME, GLOBAL_FUNDS_ASSET_ID, GLOBAL_IMAGE_ASSET_ID,
+        GLOBAL_IMAGE_URL, GLOBAL_INVESTORS_SHARE, GLOBAL_MAX_INVEST_AMOUNT,
+        GLOBAL_MIN_INVEST_AMOUNT, GLOBAL_PROSPECTUS_HASH, GLOBAL_PROSPECTUS_URL, GLOBAL_RAISED,
+        GLOBAL_SETUP_DATE, GLOBAL_SHARES_ASSET_ID, GLOBAL_SHARES_LOCKED, GLOBAL_SHARE_PRICE,
+        GLOBAL_SOCIAL_MEDIA_URL, GLOBAL_TARGET, GLOBAL_TARGET_END_DATE, GLOBAL_TEAM_URL,
+        GLOBAL_TOTAL_RECEIVED, GLOBAL_VERSIONS, GLOBAL_WITHDRAWABLE_AMOUNT, LOCAL_CLAIMED_INIT,
+        LOCAL_CLAIMED_TOTAL, LOCAL_SHARES, LOCAL_SIGNED_PROSPECTUS_HASH,
+        LOCAL_SIGNED_PROSPECTUS_TIMESTAMP, LOCAL_SIGNED_PROSPECTUS_URL,
+    },
 };
 use crate::{
-    api::version::{bytes_to_versions, Version},
+    api::version::{bytes_to_versions, versions_to_bytes, Version, Versions},
+    chain::chain_client::ChainClient,
     models::{
         dao_app_id::DaoAppId,
         funds::{FundsAmount, FundsAssetId},
         hashable::hash,
         nft::Nft,
+        setup_dao_specs::SetupDaoSpecs,
         share_amount::ShareAmount,
         shares_percentage::SharesPercentage,
         timestamp::Timestamp,
     },
 };
 use algonaut::{
-    algod::v2::Algod,
     core::Address,
-    model::algod::v2::{Account, ApplicationLocalState, TealKeyValue, TealValue},
+    model::algod::v2::{Account, Application, ApplicationLocalState, TealKeyValue, TealValue},
 };
 use anyhow::{anyhow, Result};
 use data_encoding::{BASE64, HEXLOWER};
+use futures::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
-    convert::TryInto,
+    convert::{TryFrom, TryInto},
 };
 
-const GLOBAL_TOTAL_RECEIVED: AppStateKey = AppStateKey("CentralReceivedTotal");
-const GLOBAL_WITHDRAWABLE_AMOUNT: AppStateKey = AppStateKey("AvailableAmount");
-
-const GLOBAL_FUNDS_ASSET_ID: AppStateKey = AppStateKey("FundsAssetId");
-const GLOBAL_SHARES_ASSET_ID: AppStateKey = AppStateKey("SharesAssetId");
-
-const GLOBAL_DAO_NAME: AppStateKey = AppStateKey("DaoName");
-const GLOBAL_DAO_DESC: AppStateKey = AppStateKey("DaoDesc");
-const GLOBAL_SHARE_PRICE: AppStateKey = AppStateKey("SharePrice");
-const GLOBAL_INVESTORS_SHARE: AppStateKey = AppStateKey("InvestorsPart");
-
-const GLOBAL_IMAGE_URL: AppStateKey = AppStateKey("ImageUrl");
-const GLOBAL_IMAGE_ASSET_ID: AppStateKey = AppStateKey("ImageAsset");
-const GLOBAL_SOCIAL_MEDIA_URL: AppStateKey = AppStateKey("SocialMediaUrl");
-const GLOBAL_PROSPECTUS_URL: AppStateKey = AppStateKey("ProspectusUrl");
-const GLOBAL_PROSPECTUS_HASH: AppStateKey = AppStateKey("ProspectusHash");
-
-const GLOBAL_SHARES_LOCKED: AppStateKey = AppStateKey("LockedShares");
-
-const GLOBAL_VERSIONS: AppStateKey = AppStateKey("Versions");
-
-const GLOBAL_TARGET: AppStateKey = AppStateKey("Target");
-const GLOBAL_TARGET_END_DATE: AppStateKey = AppStateKey("TargetEndDate");
-const GLOBAL_RAISED: AppStateKey = AppStateKey("Raised");
-
-const GLOBAL_MIN_INVEST_AMOUNT: AppStateKey = AppStateKey("GlobalMinInvestAmount");
-const GLOBAL_MAX_INVEST_AMOUNT: AppStateKey = AppStateKey("GlobalMaxInvestAmount");
-
-const GLOBAL_TEAM_URL: AppStateKey = AppStateKey("TeamUrl");
-
-const LOCAL_CLAIMED_TOTAL: AppStateKey = AppStateKey("ClaimedTotal");
-const LOCAL_CLAIMED_INIT: AppStateKey = AppStateKey("ClaimedInit");
-const LOCAL_SHARES: AppStateKey = AppStateKey("Shares");
-const LOCAL_SIGNED_PROSPECTUS_URL: AppStateKey = AppStateKey("SignedProspectusUrl");
-const LOCAL_SIGNED_PROSPECTUS_HASH: AppStateKey = AppStateKey("SignedProspectusHash");
-const LOCAL_SIGNED_PROSPECTUS_TIMESTAMP: AppStateKey = AppStateKey("SignedProspectusTimestamp");
-
-const GLOBAL_SETUP_DATE: AppStateKey = AppStateKey("SetupDate");
-
-// dao name, dao descr, social media, versions, image nft url, prospectus url, prospectus hash, team url
-pub const GLOBAL_SCHEMA_NUM_BYTE_SLICES: u64 = 8;
-// total received, shares asset id, funds asset id, share price, investors part, shares locked, funds target, funds target date,
-// raised, image nft asset id, setup date, min invest shares, max invest shares
-pub const GLOBAL_SCHEMA_NUM_INTS: u64 = 14;
-
-pub const LOCAL_SCHEMA_NUM_BYTE_SLICES: u64 = 3; // signed prospectus url, signed prospectus hash, signed prospectus timestamp
-pub const LOCAL_SCHEMA_NUM_INTS: u64 = 3; // for investors: "shares", "claimed total", "claimed init"
+pub use super::dao_state_schema::{
+    GLOBAL_SCHEMA_NUM_BYTE_SLICES, GLOBAL_SCHEMA_NUM_INTS, LOCAL_SCHEMA_NUM_BYTE_SLICES,
+    LOCAL_SCHEMA_NUM_INTS,
+};
 
 // TODO rename in DaoGlobalState
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -127,150 +101,686 @@ pub struct CentralAppGlobalState {
 }
 
 /// Returns Ok only if called after dao setup (branch_setup_dao), where all the global state is initialized.
-pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<CentralAppGlobalState> {
-    let app = algod.application_information(app_id.0).await?;
-    let gs = ApplicationGlobalState(app.params.global_state);
+pub async fn dao_global_state(
+    algod: &impl ChainClient,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    let app = algod
+        .application_information(app_id.0)
+        .await
+        .map_err(|e| DaoGlobalStateError::Network(e.to_string()))?;
+    dao_global_state_from_app(&app)
+}
+
+/// Fetches the global state of multiple DAOs, with at most `concurrency` requests in flight.
+/// Results are in the same order as `app_ids` and errors are per DAO, i.e. a failing DAO doesn't fail the batch.
+pub async fn dao_global_states(
+    algod: &impl ChainClient,
+    app_ids: &[DaoAppId],
+    concurrency: usize,
+) -> Vec<(DaoAppId, Result<CentralAppGlobalState, DaoGlobalStateError>)> {
+    stream::iter(app_ids.iter().copied())
+        .map(|app_id| async move { (app_id, dao_global_state(algod, app_id).await) })
+        // 0 would never poll the futures
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
 
-    let expected_gs_len = GLOBAL_SCHEMA_NUM_BYTE_SLICES + GLOBAL_SCHEMA_NUM_INTS;
-    if gs.len() != expected_gs_len as usize {
-        log::debug!("DAO global state:");
-        print_state(&gs.0)?;
-        return Err(anyhow!(
-            "Unexpected global state length: {}. Expected: {expected_gs_len}. Was the DAO setup performed already?",
-            gs.len(),
-        ));
+/// Like [dao_global_state], for an already fetched application.
+pub fn dao_global_state_from_app(
+    app: &Application,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    dao_global_state_from_key_values(&app.params.global_state, app.params.creator)
+}
+
+/// Like [dao_global_state], for global state that was retrieved by other means
+/// (e.g. indexer, dry run or a cached snapshot).
+/// `creator` is the app's creator, i.e. the DAO owner.
+pub fn dao_global_state_from_key_values(
+    key_values: &[TealKeyValue],
+    creator: Address,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    Ok(dao_global_state_and_layout_from_key_values(key_values, creator)?.0)
+}
+
+/// Like [dao_global_state], also returning the layout the state was decoded with.
+pub async fn dao_global_state_and_layout(
+    algod: &impl ChainClient,
+    app_id: DaoAppId,
+) -> Result<(CentralAppGlobalState, &'static GlobalStateLayout), DaoGlobalStateError> {
+    let app = algod
+        .application_information(app_id.0)
+        .await
+        .map_err(|e| DaoGlobalStateError::Network(e.to_string()))?;
+    dao_global_state_and_layout_from_key_values(&app.params.global_state, app.params.creator)
+}
+
+pub fn dao_global_state_and_layout_from_key_values(
+    key_values: &[TealKeyValue],
+    creator: Address,
+) -> Result<(CentralAppGlobalState, &'static GlobalStateLayout), DaoGlobalStateError> {
+    let gs = ApplicationGlobalState(key_values.to_vec());
+
+    let layout = stored_state_layout(&gs)?;
+    validate_state(&gs, layout.schema, StateScope::Global)?;
+    let state = (layout.decode)(&gs, creator, layout.schema)?;
+
+    Ok((state, layout))
+}
+
+/// Layout of the app approval version stored in the global state.
+/// If the versions aren't set yet (DAO not set up), the latest: validating with it reports what's missing.
+pub fn stored_state_layout(
+    gs: &ApplicationGlobalState,
+) -> Result<&'static GlobalStateLayout, DaoGlobalStateError> {
+    match find_bytes(gs, &GLOBAL_VERSIONS)? {
+        Some(bytes) => {
+            let versions = bytes_to_versions(&bytes)
+                .map_err(|_| DaoGlobalStateError::BadVersionsEncoding(bytes))?;
+            version_state_layout(versions.app_approval)
+        }
+        None => Ok(latest_global_state_layout()),
     }
+}
 
-    let total_received = FundsAmount::new(get_int_or_err(&GLOBAL_TOTAL_RECEIVED, &gs)?);
-    let available = FundsAmount::new(get_int_or_err(&GLOBAL_WITHDRAWABLE_AMOUNT, &gs)?);
+/// How the state (global and local) of an app approval version is structured.
+/// When a new app approval version changes the state, a layout has to be added to [GLOBAL_STATE_LAYOUTS],
+/// so DAOs with older versions can still be decoded.
+#[derive(Debug)]
+pub struct GlobalStateLayout {
+    pub version: Version,
+    pub schema: &'static [StateKeySpec],
+    decode: fn(
+        &ApplicationGlobalState,
+        Address,
+        &'static [StateKeySpec],
+    ) -> Result<CentralAppGlobalState, DaoGlobalStateError>,
+}
 
-    let funds_asset_id = FundsAssetId(get_int_or_err(&GLOBAL_FUNDS_ASSET_ID, &gs)?);
-    let shares_asset_id = get_int_or_err(&GLOBAL_SHARES_ASSET_ID, &gs)?;
+impl GlobalStateLayout {
+    /// Number of (byte slice, uint) keys in the scope
+    pub fn schema_size(&self, scope: StateScope) -> (u64, u64) {
+        let count = |value_type: StateValueType| {
+            schema_keys_in_scope(self.schema, scope)
+                .filter(|spec| spec.value_type == value_type)
+                .count() as u64
+        };
+        (count(StateValueType::Bytes), count(StateValueType::Uint))
+    }
+}
 
-    let project_name = String::from_utf8(get_bytes_or_err(&GLOBAL_DAO_NAME, &gs)?)?;
-    let project_desc_url = read_string_none_if_empty(&gs, &GLOBAL_DAO_DESC)?;
+/// Ordered by version, the last being the current one
+pub const GLOBAL_STATE_LAYOUTS: &[GlobalStateLayout] = &[GlobalStateLayout {
+    version: Version(1),
+    schema: DAO_STATE_SCHEMA,
+    decode: decode_global_state_v1,
+}];
+
+pub fn global_state_layout(version: Version) -> Option<&'static GlobalStateLayout> {
+    GLOBAL_STATE_LAYOUTS
+        .iter()
+        .find(|layout| layout.version == version)
+}
 
-    let share_price = FundsAmount::new(get_int_or_err(&GLOBAL_SHARE_PRICE, &gs)?);
-    let investors_share = get_int_or_err(&GLOBAL_INVESTORS_SHARE, &gs)?.try_into()?;
+fn version_state_layout(
+    version: Version,
+) -> Result<&'static GlobalStateLayout, DaoGlobalStateError> {
+    global_state_layout(version).ok_or(DaoGlobalStateError::UnsupportedVersion(version))
+}
 
-    let image_asset_id = gs.find_uint(&GLOBAL_IMAGE_ASSET_ID);
-    let image_url = gs.find_bytes(&GLOBAL_IMAGE_URL);
-    let image_nft = match (image_asset_id, image_url) {
-        // default values - meaning we didn't set them (they were just initialized in teal)
-        (Some(asset_id), Some(url_bytes)) if asset_id == 0 && url_bytes.is_empty() => None,
-        (Some(asset_id), Some(url_bytes)) => Some(Nft {
-            asset_id,
-            url: String::from_utf8(url_bytes)?,
+pub fn latest_global_state_layout() -> &'static GlobalStateLayout {
+    // the registry is a non empty constant
+    &GLOBAL_STATE_LAYOUTS[GLOBAL_STATE_LAYOUTS.len() - 1]
+}
+
+/// Expects the state to be validated against the v1 schema
+fn decode_global_state_v1(
+    gs: &ApplicationGlobalState,
+    owner: Address,
+    schema: &'static [StateKeySpec],
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    partial_dao_global_state_from_state(gs, owner, schema)?.try_into()
+}
+
+/// Global state of a DAO that may not be (completely) set up, or that has a different schema.
+/// Each field is None if its key isn't in the global state.
+/// Fields of optional keys (see [Presence]) are wrapped in another Option,
+/// e.g. `team_url: Some(None)` means that the key isn't set or has the unset value (empty).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialDaoGlobalState {
+    pub received: Option<FundsAmount>,
+    pub available: Option<FundsAmount>,
+    pub versions: Option<Versions>,
+    pub funds_asset_id: Option<FundsAssetId>,
+    pub shares_asset_id: Option<u64>,
+    pub project_name: Option<String>,
+    pub project_desc_url: Option<Option<String>>,
+    pub share_price: Option<FundsAmount>,
+    pub investors_share: Option<SharesPercentage>,
+    /// None if any of the nft keys (asset id, url) isn't set
+    pub image_nft: Option<Option<Nft>>,
+    pub social_media_url: Option<String>,
+    /// None if any of the prospectus keys (url, hash) isn't set
+    pub prospectus: Option<Option<Prospectus>>,
+    pub owner: Address,
+    pub locked_shares: Option<ShareAmount>,
+    pub min_funds_target: Option<FundsAmount>,
+    pub min_funds_target_end_date: Option<Timestamp>,
+    pub raised: Option<FundsAmount>,
+    pub setup_date: Option<Timestamp>,
+    pub min_invest_amount: Option<ShareAmount>,
+    pub max_invest_amount: Option<ShareAmount>,
+    pub team_url: Option<Option<String>>,
+
+    pub setup_progress: SetupProgress,
+}
+
+/// Which of the schema's global state keys are set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetupProgress {
+    pub present: Vec<AppStateKey<'static>>,
+    pub missing: Vec<AppStateKey<'static>>,
+    /// Keys that aren't in the schema (e.g. from a newer app version)
+    pub unknown: Vec<String>,
+}
+
+impl SetupProgress {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Like [dao_global_state], but doesn't fail if keys are missing.
+/// Present keys are still expected to have valid values.
+pub async fn dao_partial_global_state(
+    algod: &impl ChainClient,
+    app_id: DaoAppId,
+) -> Result<PartialDaoGlobalState, DaoGlobalStateError> {
+    let app = algod
+        .application_information(app_id.0)
+        .await
+        .map_err(|e| DaoGlobalStateError::Network(e.to_string()))?;
+    partial_dao_global_state_from_key_values(&app.params.global_state, app.params.creator)
+}
+
+pub fn partial_dao_global_state_from_key_values(
+    key_values: &[TealKeyValue],
+    creator: Address,
+) -> Result<PartialDaoGlobalState, DaoGlobalStateError> {
+    let gs = ApplicationGlobalState(key_values.to_vec());
+    let layout = stored_state_layout(&gs)?;
+    partial_dao_global_state_from_state(&gs, creator, layout.schema)
+}
+
+/// `schema` is the one of the stored version, to report the setup progress
+fn partial_dao_global_state_from_state(
+    gs: &ApplicationGlobalState,
+    owner: Address,
+    schema: &'static [StateKeySpec],
+) -> Result<PartialDaoGlobalState, DaoGlobalStateError> {
+    // for required keys: only optional ones can be read as Some(None)
+    let int = |key| Ok::<_, DaoGlobalStateError>(find_int_in_schema(gs, schema, key)?.flatten());
+    let string =
+        |key| Ok::<_, DaoGlobalStateError>(find_string_in_schema(gs, schema, key)?.flatten());
+
+    let investors_share = match int(&GLOBAL_INVESTORS_SHARE)? {
+        Some(share) => Some(share.try_into().map_err(|e: anyhow::Error| {
+            DaoGlobalStateError::InvalidValue(GLOBAL_INVESTORS_SHARE, e.to_string())
+        })?),
+        None => None,
+    };
+
+    let image_nft = match (
+        find_int_in_schema(gs, schema, &GLOBAL_IMAGE_ASSET_ID)?,
+        find_string_in_schema(gs, schema, &GLOBAL_IMAGE_URL)?,
+    ) {
+        (Some(asset_id), Some(url)) => Some(match (asset_id, url) {
+            (Some(asset_id), Some(url)) => Some(Nft { asset_id, url }),
+            (None, None) => None,
+            _ => {
+                return Err(DaoGlobalStateError::InconsistentPair(
+                    GLOBAL_IMAGE_ASSET_ID,
+                    GLOBAL_IMAGE_URL,
+                ))
+            }
         }),
-        (None, None) => None,
-        _ => {
-            return Err(anyhow!(
-                "Invalid state: nft asset id and url must both be set or not set".to_owned()
-            ))
-        }
+        _ => None,
     };
 
-    let prospectus_url = read_string_none_if_empty(&gs, &GLOBAL_PROSPECTUS_URL)?;
-    let prospectus_hash = read_string_none_if_empty(&gs, &GLOBAL_PROSPECTUS_HASH)?;
-    let prospectus = match (prospectus_url, prospectus_hash) {
-        (Some(url), Some(hash)) => Some(Prospectus { hash, url }),
-        (None, None) => None,
-        _ => {
-            return Err(anyhow!(
-                "Invalid state: prospectus hash and url must both be set or not set".to_owned()
-            ))
-        }
+    let prospectus = match (
+        find_string_in_schema(gs, schema, &GLOBAL_PROSPECTUS_URL)?,
+        find_string_in_schema(gs, schema, &GLOBAL_PROSPECTUS_HASH)?,
+    ) {
+        (Some(url), Some(hash)) => Some(match (url, hash) {
+            (Some(url), Some(hash)) => Some(Prospectus { hash, url }),
+            (None, None) => None,
+            _ => {
+                return Err(DaoGlobalStateError::InconsistentPair(
+                    GLOBAL_PROSPECTUS_URL,
+                    GLOBAL_PROSPECTUS_HASH,
+                ))
+            }
+        }),
+        _ => None,
     };
 
-    let social_media_url = String::from_utf8(get_bytes_or_err(&GLOBAL_SOCIAL_MEDIA_URL, &gs)?)?;
+    let versions = match find_bytes_in_schema(gs, schema, &GLOBAL_VERSIONS)?.flatten() {
+        Some(bytes) => Some(
+            bytes_to_versions(&bytes)
+                .map_err(|_| DaoGlobalStateError::BadVersionsEncoding(bytes))?,
+        ),
+        None => None,
+    };
 
-    let versions_bytes = get_bytes_or_err(&GLOBAL_VERSIONS, &gs)?;
-    let versions = bytes_to_versions(&versions_bytes)?;
+    Ok(PartialDaoGlobalState {
+        received: int(&GLOBAL_TOTAL_RECEIVED)?.map(FundsAmount::new),
+        available: int(&GLOBAL_WITHDRAWABLE_AMOUNT)?.map(FundsAmount::new),
+        versions,
+        funds_asset_id: int(&GLOBAL_FUNDS_ASSET_ID)?.map(FundsAssetId),
+        shares_asset_id: int(&GLOBAL_SHARES_ASSET_ID)?,
+        project_name: string(&GLOBAL_DAO_NAME)?,
+        project_desc_url: find_string_in_schema(gs, schema, &GLOBAL_DAO_DESC)?,
+        share_price: int(&GLOBAL_SHARE_PRICE)?.map(FundsAmount::new),
+        investors_share,
+        image_nft,
+        social_media_url: string(&GLOBAL_SOCIAL_MEDIA_URL)?,
+        prospectus,
+        owner,
+        locked_shares: int(&GLOBAL_SHARES_LOCKED)?.map(ShareAmount::new),
+        min_funds_target: int(&GLOBAL_TARGET)?.map(FundsAmount::new),
+        min_funds_target_end_date: int(&GLOBAL_TARGET_END_DATE)?.map(Timestamp),
+        raised: int(&GLOBAL_RAISED)?.map(FundsAmount::new),
+        setup_date: int(&GLOBAL_SETUP_DATE)?.map(Timestamp),
+        min_invest_amount: int(&GLOBAL_MIN_INVEST_AMOUNT)?.map(ShareAmount::new),
+        max_invest_amount: int(&GLOBAL_MAX_INVEST_AMOUNT)?.map(ShareAmount::new),
+        team_url: find_string_in_schema(gs, schema, &GLOBAL_TEAM_URL)?,
+        setup_progress: setup_progress(gs, schema),
+    })
+}
 
-    let shares_locked = ShareAmount::new(get_int_or_err(&GLOBAL_SHARES_LOCKED, &gs)?);
+fn setup_progress(gs: &ApplicationGlobalState, schema: &'static [StateKeySpec]) -> SetupProgress {
+    let (present, missing) = schema_keys_in_scope(schema, StateScope::Global)
+        .map(|spec| spec.key.clone())
+        .partition(|key| gs.find(key).is_some());
+
+    let unknown =
+        gs.0.iter()
+            .filter(|kv| {
+                !schema_keys_in_scope(schema, StateScope::Global)
+                    .any(|spec| kv.key == spec.key.to_teal_encoded_str())
+            })
+            .map(|kv| {
+                BASE64
+                    .decode(kv.key.as_bytes())
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+                    // show the raw key if it can't be decoded
+                    .unwrap_or_else(|| kv.key.clone())
+            })
+            .collect();
+
+    SetupProgress {
+        present,
+        missing,
+        unknown,
+    }
+}
 
-    let min_funds_target = FundsAmount::new(get_int_or_err(&GLOBAL_TARGET, &gs)?);
-    let min_funds_target_end_date = Timestamp(get_int_or_err(&GLOBAL_TARGET_END_DATE, &gs)?);
-    let raised = FundsAmount::new(get_int_or_err(&GLOBAL_RAISED, &gs)?);
+impl TryFrom<PartialDaoGlobalState> for CentralAppGlobalState {
+    type Error = DaoGlobalStateError;
+
+    fn try_from(state: PartialDaoGlobalState) -> Result<Self, Self::Error> {
+        let versions = required(state.versions, &GLOBAL_VERSIONS, StateValueType::Bytes)?;
+        Ok(CentralAppGlobalState {
+            received: required(state.received, &GLOBAL_TOTAL_RECEIVED, StateValueType::Uint)?,
+            available: required(
+                state.available,
+                &GLOBAL_WITHDRAWABLE_AMOUNT,
+                StateValueType::Uint,
+            )?,
+            app_approval_version: versions.app_approval,
+            app_clear_version: versions.app_clear,
+            funds_asset_id: required(
+                state.funds_asset_id,
+                &GLOBAL_FUNDS_ASSET_ID,
+                StateValueType::Uint,
+            )?,
+            shares_asset_id: required(
+                state.shares_asset_id,
+                &GLOBAL_SHARES_ASSET_ID,
+                StateValueType::Uint,
+            )?,
+            project_name: required(state.project_name, &GLOBAL_DAO_NAME, StateValueType::Bytes)?,
+            project_desc_url: required(
+                state.project_desc_url,
+                &GLOBAL_DAO_DESC,
+                StateValueType::Bytes,
+            )?,
+            share_price: required(state.share_price, &GLOBAL_SHARE_PRICE, StateValueType::Uint)?,
+            investors_share: required(
+                state.investors_share,
+                &GLOBAL_INVESTORS_SHARE,
+                StateValueType::Uint,
+            )?,
+            image_nft: required(state.image_nft, &GLOBAL_IMAGE_URL, StateValueType::Bytes)?,
+            social_media_url: required(
+                state.social_media_url,
+                &GLOBAL_SOCIAL_MEDIA_URL,
+                StateValueType::Bytes,
+            )?,
+            prospectus: required(
+                state.prospectus,
+                &GLOBAL_PROSPECTUS_URL,
+                StateValueType::Bytes,
+            )?,
+            owner: state.owner,
+            locked_shares: required(
+                state.locked_shares,
+                &GLOBAL_SHARES_LOCKED,
+                StateValueType::Uint,
+            )?,
+            min_funds_target: required(
+                state.min_funds_target,
+                &GLOBAL_TARGET,
+                StateValueType::Uint,
+            )?,
+            min_funds_target_end_date: required(
+                state.min_funds_target_end_date,
+                &GLOBAL_TARGET_END_DATE,
+                StateValueType::Uint,
+            )?,
+            raised: required(state.raised, &GLOBAL_RAISED, StateValueType::Uint)?,
+            setup_date: required(state.setup_date, &GLOBAL_SETUP_DATE, StateValueType::Uint)?,
+            min_invest_amount: required(
+                state.min_invest_amount,
+                &GLOBAL_MIN_INVEST_AMOUNT,
+                StateValueType::Uint,
+            )?,
+            max_invest_amount: required(
+                state.max_invest_amount,
+                &GLOBAL_MAX_INVEST_AMOUNT,
+                StateValueType::Uint,
+            )?,
+            team_url: required(state.team_url, &GLOBAL_TEAM_URL, StateValueType::Bytes)?,
+        })
+    }
+}
 
-    let setup_date = Timestamp(get_int_or_err(&GLOBAL_SETUP_DATE, &gs)?);
+fn required<T>(
+    value: Option<T>,
+    key: &AppStateKey<'static>,
+    value_type: StateValueType,
+) -> Result<T, DaoGlobalStateError> {
+    value.ok_or_else(|| DaoGlobalStateError::MissingKey(key.clone(), value_type))
+}
 
-    let min_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MIN_INVEST_AMOUNT, &gs)?);
-    let max_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MAX_INVEST_AMOUNT, &gs)?);
+/// Encodes the global state the way it's stored in TEAL - the inverse of [dao_global_state].
+/// Not set optional values are encoded as the defaults TEAL initializes them with (empty bytes / 0),
+/// so e.g. `project_desc_url: Some("")` is decoded back as `None`.
+/// Note that `owner` isn't part of the global state (it's the app's creator), so it's not encoded.
+pub fn dao_global_state_to_key_values(state: &CentralAppGlobalState) -> Result<Vec<TealKeyValue>> {
+    let versions = versions_to_bytes(Versions {
+        app_approval: state.app_approval_version,
+        app_clear: state.app_clear_version,
+    })?;
+
+    let (image_asset_id, image_url) = match &state.image_nft {
+        Some(nft) => (nft.asset_id, nft.url.as_bytes().to_vec()),
+        None => (0, vec![]),
+    };
+
+    let (prospectus_url, prospectus_hash) = match &state.prospectus {
+        Some(prospectus) => (
+            prospectus.url.as_bytes().to_vec(),
+            prospectus.hash.as_bytes().to_vec(),
+        ),
+        None => (vec![], vec![]),
+    };
 
-    let team_url = read_string_none_if_empty(&gs, &GLOBAL_TEAM_URL)?;
+    Ok(vec![
+        uint_key_value(&GLOBAL_TOTAL_RECEIVED, state.received.val()),
+        uint_key_value(&GLOBAL_WITHDRAWABLE_AMOUNT, state.available.val()),
+        uint_key_value(&GLOBAL_FUNDS_ASSET_ID, state.funds_asset_id.0),
+        uint_key_value(&GLOBAL_SHARES_ASSET_ID, state.shares_asset_id),
+        bytes_key_value(&GLOBAL_DAO_NAME, state.project_name.as_bytes().to_vec()),
+        bytes_key_value(&GLOBAL_DAO_DESC, string_or_empty(&state.project_desc_url)),
+        uint_key_value(&GLOBAL_SHARE_PRICE, state.share_price.val()),
+        uint_key_value(&GLOBAL_INVESTORS_SHARE, state.investors_share.to_u64()?),
+        bytes_key_value(&GLOBAL_IMAGE_URL, image_url),
+        uint_key_value(&GLOBAL_IMAGE_ASSET_ID, image_asset_id),
+        bytes_key_value(
+            &GLOBAL_SOCIAL_MEDIA_URL,
+            state.social_media_url.as_bytes().to_vec(),
+        ),
+        bytes_key_value(&GLOBAL_PROSPECTUS_URL, prospectus_url),
+        bytes_key_value(&GLOBAL_PROSPECTUS_HASH, prospectus_hash),
+        uint_key_value(&GLOBAL_SHARES_LOCKED, state.locked_shares.val()),
+        bytes_key_value(&GLOBAL_VERSIONS, versions),
+        uint_key_value(&GLOBAL_TARGET, state.min_funds_target.val()),
+        uint_key_value(&GLOBAL_TARGET_END_DATE, state.min_funds_target_end_date.0),
+        uint_key_value(&GLOBAL_RAISED, state.raised.val()),
+        uint_key_value(&GLOBAL_MIN_INVEST_AMOUNT, state.min_invest_amount.val()),
+        uint_key_value(&GLOBAL_MAX_INVEST_AMOUNT, state.max_invest_amount.val()),
+        bytes_key_value(&GLOBAL_TEAM_URL, string_or_empty(&state.team_url)),
+        uint_key_value(&GLOBAL_SETUP_DATE, state.setup_date.0),
+    ])
+}
+
+/// The global state right after the DAO setup, i.e. before any investments, payments etc.
+/// The ids / versions / date are passed separately, as they're not known when creating the specs.
+#[allow(clippy::too_many_arguments)]
+pub fn dao_global_state_after_setup(
+    specs: &SetupDaoSpecs,
+    owner: Address,
+    funds_asset_id: FundsAssetId,
+    shares_asset_id: u64,
+    image_asset_id: Option<u64>,
+    versions: Versions,
+    setup_date: Timestamp,
+) -> Result<CentralAppGlobalState> {
+    let image_nft = match (image_asset_id, &specs.image_url) {
+        (Some(asset_id), Some(url)) => Some(Nft {
+            asset_id,
+            url: url.clone(),
+        }),
+        (None, None) => None,
+        _ => {
+            return Err(anyhow!(
+                "Invalid specs: nft asset id and url must both be set or not set"
+            ))
+        }
+    };
 
     Ok(CentralAppGlobalState {
-        received: total_received,
-        available,
+        received: FundsAmount::new(0),
+        available: FundsAmount::new(0),
         app_approval_version: versions.app_approval,
         app_clear_version: versions.app_clear,
         funds_asset_id,
         shares_asset_id,
-        project_name,
-        project_desc_url,
-        share_price,
-        investors_share,
+        project_name: specs.name.clone(),
+        project_desc_url: specs.descr_url.clone(),
+        share_price: specs.share_price,
+        investors_share: specs.investors_share,
         image_nft,
-        social_media_url,
-        prospectus,
-        owner: app.params.creator,
-        locked_shares: shares_locked,
-        min_funds_target,
-        min_funds_target_end_date,
-        raised,
+        social_media_url: specs.social_media_url.clone(),
+        prospectus: specs.prospectus.clone(),
+        owner,
+        locked_shares: ShareAmount::new(0),
+        min_funds_target: specs.raise_min_target,
+        min_funds_target_end_date: specs.raise_end_date,
+        raised: FundsAmount::new(0),
         setup_date,
-        min_invest_amount,
-        max_invest_amount,
-        team_url,
+        min_invest_amount: specs.min_invest_amount,
+        max_invest_amount: specs.max_invest_amount,
+        team_url: None,
     })
 }
 
-fn read_string_none_if_empty<T>(gs: &T, key: &AppStateKey) -> Result<Option<String>>
+fn string_or_empty(str: &Option<String>) -> Vec<u8> {
+    str.as_ref()
+        .map(|s| s.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Reads the key as its presence in the schema says: None if the key isn't set, and for optional keys
+/// `Some(None)` if the key isn't set or has the unset value (0).
+/// Keys that aren't in the schema are read as required.
+fn find_int_in_schema<T>(
+    state: &T,
+    schema: &'static [StateKeySpec],
+    key: &AppStateKey<'static>,
+) -> Result<Option<Option<u64>>, DaoGlobalStateError>
+where
+    T: ApplicationStateExt,
+{
+    Ok(apply_presence(
+        schema,
+        key,
+        find_int(state, key)?,
+        |value| *value == 0,
+    ))
+}
+
+/// Like [find_int_in_schema], the unset value being empty bytes
+fn find_bytes_in_schema<T>(
+    state: &T,
+    schema: &'static [StateKeySpec],
+    key: &AppStateKey<'static>,
+) -> Result<Option<Option<Vec<u8>>>, DaoGlobalStateError>
+where
+    T: ApplicationStateExt,
+{
+    Ok(apply_presence(
+        schema,
+        key,
+        find_bytes(state, key)?,
+        |value| value.is_empty(),
+    ))
+}
+
+fn find_string_in_schema<T>(
+    state: &T,
+    schema: &'static [StateKeySpec],
+    key: &AppStateKey<'static>,
+) -> Result<Option<Option<String>>, DaoGlobalStateError>
 where
     T: ApplicationStateExt,
 {
-    Ok(match read_bytes_none_if_empty(gs, key) {
-        // guaranteed to not be empty here
-        Some(bytes) => Some(String::from_utf8(bytes)?),
+    match find_bytes_in_schema(state, schema, key)? {
+        Some(Some(bytes)) => Ok(Some(Some(to_string(bytes, key)?))),
+        Some(None) => Ok(Some(None)),
+        None => Ok(None),
+    }
+}
+
+fn apply_presence<V>(
+    schema: &'static [StateKeySpec],
+    key: &AppStateKey<'static>,
+    value: Option<V>,
+    is_unset: impl Fn(&V) -> bool,
+) -> Option<Option<V>> {
+    let optional = schema
+        .iter()
+        .any(|spec| &spec.key == key && spec.presence == Presence::Optional);
+    match value {
+        Some(value) if optional && is_unset(&value) => Some(None),
+        Some(value) => Some(Some(value)),
+        None if optional => Some(None),
         None => None,
-    })
+    }
+}
+
+fn to_string(bytes: Vec<u8>, key: &AppStateKey<'static>) -> Result<String, DaoGlobalStateError> {
+    String::from_utf8(bytes).map_err(|_| DaoGlobalStateError::InvalidUtf8(key.clone()))
 }
 
-fn read_bytes_none_if_empty<T>(gs: &T, key: &AppStateKey) -> Option<Vec<u8>>
+/// Checks that the state has the keys of the schema, with the expected types.
+/// Optional keys (see [Presence]) may be missing: they're read as unset.
+fn validate_state<T>(
+    state: &T,
+    schema: &'static [StateKeySpec],
+    scope: StateScope,
+) -> Result<(), DaoGlobalStateError>
 where
     T: ApplicationStateExt,
 {
-    match gs.find_bytes(key) {
-        Some(bytes) => {
-            if bytes.is_empty() {
-                None
-            } else {
-                Some(bytes)
+    let expected_len = schema_keys_in_scope(schema, scope).count();
+    let mut schema_keys_len = 0;
+    let mut missing_required = None;
+    for spec in schema_keys_in_scope(schema, scope) {
+        match find_value(state, &spec.key, spec.value_type)? {
+            Some(_) => schema_keys_len += 1,
+            None if spec.presence == Presence::Required && missing_required.is_none() => {
+                missing_required = Some(spec)
             }
+            None => {}
         }
-        None => None,
     }
+
+    // keys that aren't in the schema, or required keys missing
+    let res = if state.len() != schema_keys_len {
+        Err(DaoGlobalStateError::UnexpectedLength {
+            len: state.len(),
+            expected: expected_len,
+        })
+    } else {
+        match missing_required {
+            Some(_) if scope == StateScope::Global => Err(DaoGlobalStateError::NotSetUp {
+                len: state.len(),
+                expected: expected_len,
+            }),
+            Some(spec) => Err(DaoGlobalStateError::MissingKey(
+                spec.key.clone(),
+                spec.value_type,
+            )),
+            None => Ok(()),
+        }
+    };
+
+    if res.is_err() {
+        log::debug!("DAO {scope:?} state:");
+        if let Err(e) = print_state(&state.key_values(), schema, scope) {
+            log::error!("Error printing state: {e}");
+        }
+    }
+    res
 }
 
-fn print_state(values: &[TealKeyValue]) -> Result<()> {
+/// Prints the schema keys first (in schema order), then keys that aren't in the schema
+fn print_state(
+    values: &[TealKeyValue],
+    schema: &'static [StateKeySpec],
+    scope: StateScope,
+) -> Result<()> {
     let mut key_values = BTreeMap::new();
     for kv in values {
         let key_bytes = BASE64.decode(kv.key.as_bytes())?;
         key_values.insert(String::from_utf8(key_bytes)?, value_to_str(&kv.value)?);
     }
 
-    // separate step in case we split the fn
+    for spec in schema_keys_in_scope(schema, scope) {
+        match key_values.remove(spec.key.0) {
+            Some(v) => log::debug!("{} => {v:?}", spec.key.0),
+            None => log::debug!(
+                "{} => not set (expected: {:?}, {:?})",
+                spec.key.0,
+                spec.value_type,
+                spec.presence
+            ),
+        }
+    }
     for (k, v) in key_values {
-        log::debug!("{k} => {v:?}")
+        log::debug!("{k} => {v:?} (not in schema)")
     }
 
     Ok(())
 }
 
 fn value_to_str(value: &TealValue) -> Result<String> {
-    match &value.value_type {
-        1 => Ok(value
+    match value.value_type {
+        TEAL_VALUE_TYPE_BYTES => Ok(value
             .bytes
             .clone()
             .try_into()
@@ -278,7 +788,7 @@ fn value_to_str(value: &TealValue) -> Result<String> {
             .map(|array| Address(array).to_string())
             // if not address, display as hex
             .unwrap_or_else(|_| to_hex_str(&value.bytes.clone()))),
-        2 => Ok(value.uint.to_string()),
+        TEAL_VALUE_TYPE_UINT => Ok(value.uint.to_string()),
         _ => Err(anyhow!(
             "Unexpected global value type: {}",
             value.value_type
@@ -290,22 +800,50 @@ fn to_hex_str(bytes: &[u8]) -> String {
     format!("0x{}", HEXLOWER.encode(bytes))
 }
 
-fn get_int_or_err(key: &AppStateKey, gs: &ApplicationGlobalState) -> Result<u64> {
-    gs.find_uint(key).ok_or_else(|| {
-        anyhow!(
-            "Key: {key:?} (int) not set in global state: {gs:?}, global state len: {}",
-            gs.len()
-        )
-    })
+/// Returns the value if the key is set, and an error if it's set with a different type
+fn find_value<T>(
+    state: &T,
+    key: &AppStateKey<'static>,
+    value_type: StateValueType,
+) -> Result<Option<TealValue>, DaoGlobalStateError>
+where
+    T: ApplicationStateExt,
+{
+    match state.find(key) {
+        Some(value) => {
+            let expected_teal_type = match value_type {
+                StateValueType::Uint => TEAL_VALUE_TYPE_UINT,
+                StateValueType::Bytes => TEAL_VALUE_TYPE_BYTES,
+            };
+            if value.value_type == expected_teal_type {
+                Ok(Some(value))
+            } else {
+                Err(DaoGlobalStateError::WrongType {
+                    key: key.clone(),
+                    expected: value_type,
+                    actual: value.value_type,
+                })
+            }
+        }
+        None => Ok(None),
+    }
 }
 
-fn get_bytes_or_err(key: &AppStateKey, gs: &ApplicationGlobalState) -> Result<Vec<u8>> {
-    gs.find_bytes(key).ok_or_else(|| {
-        anyhow!(
-            "Key: {key:?} (bytes) not set in global state: {gs:?}, global state len: {}",
-            gs.len()
-        )
-    })
+fn find_int<T>(state: &T, key: &AppStateKey<'static>) -> Result<Option<u64>, DaoGlobalStateError>
+where
+    T: ApplicationStateExt,
+{
+    Ok(find_value(state, key, StateValueType::Uint)?.map(|v| v.uint))
+}
+
+fn find_bytes<T>(
+    state: &T,
+    key: &AppStateKey<'static>,
+) -> Result<Option<Vec<u8>>, DaoGlobalStateError>
+where
+    T: ApplicationStateExt,
+{
+    Ok(find_value(state, key, StateValueType::Bytes)?.map(|v| v.bytes))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -331,12 +869,17 @@ pub struct Prospectus {
 impl Prospectus {
     pub fn new(bytes: &[u8], url: String) -> Prospectus {
         Prospectus {
-            hash: BASE64.encode(&hash(bytes).0),
+            hash: prospectus_hash(bytes),
             url,
         }
     }
 }
 
+/// Hash of a prospectus document, as stored in the app state
+pub fn prospectus_hash(bytes: &[u8]) -> String {
+    BASE64.encode(&hash(bytes).0)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SignedProspectus {
     pub hash: String,
@@ -344,47 +887,73 @@ pub struct SignedProspectus {
     pub timestamp: Timestamp,
 }
 
+/// The local state is decoded with the layout of the app approval version stored in the app's global state.
 pub async fn dao_investor_state(
-    algod: &Algod,
+    algod: &impl ChainClient,
     investor: &Address,
     app_id: DaoAppId,
 ) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
     let local_state = local_state(algod, investor, app_id.0).await?;
-    central_investor_state_from_local_state(&local_state)
+    let app = algod.application_information(app_id.0).await?;
+    let layout = stored_state_layout(&ApplicationGlobalState(app.params.global_state))?;
+    central_investor_state_from_local_state(&local_state, layout)
 }
 
+/// `app_approval_version` is the one stored in the app's global state.
 pub fn central_investor_state_from_acc(
     account: &Account,
     app_id: DaoAppId,
+    app_approval_version: Version,
 ) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
     let local_state = local_state_from_account(account, app_id.0)?;
-    central_investor_state_from_local_state(&local_state)
-        .map_err(|e| ApplicationLocalStateError::Msg(e.to_string()))
+    central_investor_state_from_local_state(
+        &local_state,
+        version_state_layout(app_approval_version)?,
+    )
 }
 
-/// Expects the user to be invested (as the name indicates) - returns error otherwise.
-fn central_investor_state_from_local_state(
-    state: &ApplicationLocalState,
+/// Like [dao_investor_state], for local state that was retrieved by other means (e.g. indexer).
+/// `app_approval_version` is the one stored in the app's global state.
+pub fn central_investor_state_from_key_values(
+    key_values: &[TealKeyValue],
+    app_approval_version: Version,
 ) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
-    if state.len() != ((LOCAL_SCHEMA_NUM_BYTE_SLICES + LOCAL_SCHEMA_NUM_INTS) as usize) {
-        log::debug!("Investor local state:");
-        print_state(&state.key_value).map_err(|e| {
-            ApplicationLocalStateError::Msg(format!("Error printing local state: {e}"))
-        })?;
-        return Err(ApplicationLocalStateError::Msg(format!(
-            "Unexpected investor local state length: {}, state: {state:?}",
-            state.len(),
-        )));
-    }
-
-    let shares = get_uint_value_or_error(state, &LOCAL_SHARES)?;
-    let claimed = FundsAmount::new(get_uint_value_or_error(state, &LOCAL_CLAIMED_TOTAL)?);
-    let claimed_init = FundsAmount::new(get_uint_value_or_error(state, &LOCAL_CLAIMED_INIT)?);
-
-    let signed_prospectus_url = read_string_none_if_empty(state, &LOCAL_SIGNED_PROSPECTUS_URL)?;
-    let signed_prospectus_hash = read_string_none_if_empty(state, &LOCAL_SIGNED_PROSPECTUS_HASH)?;
+    // ApplicationGlobalState is just a key values wrapper, usable for local state too
+    central_investor_state_from_local_state(
+        &ApplicationGlobalState(key_values.to_vec()),
+        version_state_layout(app_approval_version)?,
+    )
+}
+
+/// Expects the user to be invested (as the name indicates) - returns error otherwise.
+fn central_investor_state_from_local_state<T>(
+    state: &T,
+    layout: &GlobalStateLayout,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>>
+where
+    T: ApplicationStateExt,
+{
+    let schema = layout.schema;
+    validate_state(state, schema, StateScope::Local)?;
+
+    // flatten: only optional keys can be read as Some(None)
+    let int = |key| {
+        required(
+            find_int_in_schema(state, schema, key)?.flatten(),
+            key,
+            StateValueType::Uint,
+        )
+    };
+    let shares = int(&LOCAL_SHARES)?;
+    let claimed = FundsAmount::new(int(&LOCAL_CLAIMED_TOTAL)?);
+    let claimed_init = FundsAmount::new(int(&LOCAL_CLAIMED_INIT)?);
+
+    let signed_prospectus_url =
+        find_string_in_schema(state, schema, &LOCAL_SIGNED_PROSPECTUS_URL)?.flatten();
+    let signed_prospectus_hash =
+        find_string_in_schema(state, schema, &LOCAL_SIGNED_PROSPECTUS_HASH)?.flatten();
     let signed_prospectus_timestamp =
-        read_bytes_none_if_empty(state, &LOCAL_SIGNED_PROSPECTUS_TIMESTAMP);
+        find_bytes_in_schema(state, schema, &LOCAL_SIGNED_PROSPECTUS_TIMESTAMP)?.flatten();
 
     // Note that whether None is expected or not depends on the use case:
     // currently investing requires acking the prospectus (in teal), so it should always be set
@@ -397,15 +966,31 @@ fn central_investor_state_from_local_state(
         (Some(url), Some(hash), Some(timestamp)) => Some(SignedProspectus {
             hash: hash.to_owned(),
             url: url.to_owned(),
-            timestamp: Timestamp(u64::from_be_bytes(
-                timestamp
-                    .clone()
-                    .try_into()
-                    .map_err(|e: Vec<u8>| ApplicationLocalStateError::Msg(format!("Couldn't convert vec: {:?} to timestamp. Error: {:?}", timestamp, e)))?,
-            )),
+            timestamp: Timestamp(u64::from_be_bytes(timestamp.clone().try_into().map_err(
+                |e: Vec<u8>| {
+                    DaoGlobalStateError::InvalidValue(
+                        LOCAL_SIGNED_PROSPECTUS_TIMESTAMP,
+                        format!("Couldn't convert vec: {:?} to timestamp", e),
+                    )
+                },
+            )?)),
         }),
         (None, None, None) => None,
-        _ => return Err(ApplicationLocalStateError::Msg(format!("Invalid state in teal: incomplete prospectus {signed_prospectus_url:?}, {signed_prospectus_hash:?}, {signed_prospectus_timestamp:?}"))),
+        // report the first key that doesn't match the url
+        (url, hash, _) if url.is_some() != hash.is_some() => {
+            return Err(DaoGlobalStateError::InconsistentPair(
+                LOCAL_SIGNED_PROSPECTUS_URL,
+                LOCAL_SIGNED_PROSPECTUS_HASH,
+            )
+            .into())
+        }
+        _ => {
+            return Err(DaoGlobalStateError::InconsistentPair(
+                LOCAL_SIGNED_PROSPECTUS_URL,
+                LOCAL_SIGNED_PROSPECTUS_TIMESTAMP,
+            )
+            .into())
+        }
     };
 
     Ok(CentralAppInvestorState {
@@ -416,6 +1001,84 @@ fn central_investor_state_from_local_state(
     })
 }
 
+/// Investor state of all the DAOs the investor is opted in to ("my investments").
+/// Fetches the account once and the apps of the local states with capi's schema (to know their versions),
+/// with at most `concurrency` requests in flight.
+/// Apps that don't exist anymore are skipped, other errors fail the portfolio.
+pub async fn investor_portfolio(
+    algod: &impl ChainClient,
+    investor: &Address,
+    concurrency: usize,
+) -> Result<Vec<(DaoAppId, CentralAppInvestorState)>, ApplicationLocalStateError<'static>> {
+    let account = algod.account_information(investor).await?;
+    let apps = portfolio_apps(algod, &account.apps_local_state, concurrency).await?;
+    Ok(investor_portfolio_from_acc(&account, &apps))
+}
+
+/// The apps of the local states that may be capi's
+async fn portfolio_apps(
+    algod: &impl ChainClient,
+    local_states: &[ApplicationLocalState],
+    concurrency: usize,
+) -> Result<Vec<Application>> {
+    let apps: Vec<Option<Application>> = stream::iter(
+        local_states
+            .iter()
+            .filter(|local_state| has_capi_local_schema_size(local_state)),
+    )
+    .map(|local_state| algod.find_application(local_state.id))
+    // 0 would never poll the futures
+    .buffered(concurrency.max(1))
+    .try_collect()
+    .await?;
+    // None: deleted apps - the user can still be opted in to them
+    Ok(apps.into_iter().flatten().collect())
+}
+
+/// Like [investor_portfolio], for an already fetched account and apps.
+/// Local states of apps that aren't in `apps`, that don't look like capi's, or that can't be decoded are skipped (and logged).
+pub fn investor_portfolio_from_acc(
+    account: &Account,
+    apps: &[Application],
+) -> Vec<(DaoAppId, CentralAppInvestorState)> {
+    investor_portfolio_from_local_states(&account.apps_local_state, apps)
+}
+
+fn investor_portfolio_from_local_states(
+    local_states: &[ApplicationLocalState],
+    apps: &[Application],
+) -> Vec<(DaoAppId, CentralAppInvestorState)> {
+    let apps: HashMap<u64, &Application> = apps.iter().map(|app| (app.id, app)).collect();
+    local_states
+        .iter()
+        .filter_map(|local_state| {
+            let app = apps.get(&local_state.id)?;
+            let layout =
+                match stored_state_layout(&ApplicationGlobalState(app.params.global_state.clone()))
+                {
+                    Ok(layout) => layout,
+                    Err(e) => {
+                        log::debug!("Skipping app: {}, no state layout: {e}", local_state.id);
+                        return None;
+                    }
+                };
+            if !matches_local_state_layout(local_state, layout) {
+                return None;
+            }
+            match central_investor_state_from_local_state(local_state, layout) {
+                Ok(investor_state) => Some((DaoAppId(local_state.id), investor_state)),
+                Err(e) => {
+                    log::warn!(
+                        "Skipping app: {}, invalid investor state: {e}",
+                        local_state.id
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 /// Determines whether local state belongs to a capi app
 ///
 /// it's not 100% guaranteed that the app belongs to capi - we just check for the same schema and local variable names
@@ -428,25 +1091,599 @@ fn central_investor_state_from_local_state(
 /// this can make the user open this app, thinking that it's trustable, and be more willing to invest? or something along those likes.
 /// alternative (if needed) unclear - previously we were storing the dao id in local state, but that can be imitated by other apps too.
 /// maybe it's enough to inform the user of these kind of risks with a short disclaimer
-pub fn matches_capi_local_state(app_local_state: &ApplicationLocalState) -> bool {
+/// for a stronger check (comparing the app's programs), see [super::dao_provenance::verify_dao_provenance]
+///
+/// `app_approval_version` is the one stored in the app's global state: the local state is checked against its layout.
+pub fn matches_capi_local_state(
+    app_local_state: &ApplicationLocalState,
+    app_approval_version: Version,
+) -> bool {
+    global_state_layout(app_approval_version)
+        .is_some_and(|layout| matches_local_state_layout(app_local_state, layout))
+}
+
+/// Whether the local state has the schema size of any of the layouts (cheap check before fetching the app)
+fn has_capi_local_schema_size(app_local_state: &ApplicationLocalState) -> bool {
     let schema = &app_local_state.schema;
+    GLOBAL_STATE_LAYOUTS.iter().any(|layout| {
+        (schema.num_byte_slice, schema.num_uint) == layout.schema_size(StateScope::Local)
+    })
+}
 
-    if !(schema.num_byte_slice == LOCAL_SCHEMA_NUM_BYTE_SLICES
-        && schema.num_uint == LOCAL_SCHEMA_NUM_INTS
-        // we always initialize all state, by convention
-        && app_local_state.len() == (LOCAL_SCHEMA_NUM_BYTE_SLICES + LOCAL_SCHEMA_NUM_INTS) as usize)
-    {
+fn matches_local_state_layout(
+    app_local_state: &ApplicationLocalState,
+    layout: &GlobalStateLayout,
+) -> bool {
+    let schema = &app_local_state.schema;
+
+    if (schema.num_byte_slice, schema.num_uint) != layout.schema_size(StateScope::Local) {
         return false;
     }
 
-    let state_map: HashMap<String, TealValue> = app_local_state
-        .clone()
-        .key_value
-        .into_iter()
-        .map(|kv| (kv.key, kv.value))
-        .collect();
+    // we always initialize all state, by convention
+    validate_state(app_local_state, layout.schema, StateScope::Local).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::version::{Version, Versions},
+        chain::fake_chain_client::FakeChainClient,
+        models::{
+            create_shares_specs::CreateSharesSpecs,
+            dao_app_id::DaoAppId,
+            funds::{FundsAmount, FundsAssetId},
+            nft::Nft,
+            setup_dao_specs::SetupDaoSpecs,
+            share_amount::ShareAmount,
+            timestamp::Timestamp,
+        },
+        state::{
+            app_state::{
+                bytes_key_value, uint_key_value, AppStateKey, ApplicationLocalStateError,
+                TEAL_VALUE_TYPE_BYTES, TEAL_VALUE_TYPE_UINT,
+            },
+            dao_app_state::{
+                central_investor_state_from_key_values, dao_global_state,
+                dao_global_state_after_setup, dao_global_state_and_layout_from_key_values,
+                dao_global_state_from_key_values, dao_global_state_to_key_values,
+                dao_global_states, investor_portfolio_from_local_states, matches_capi_local_state,
+                partial_dao_global_state_from_key_values, portfolio_apps, CentralAppGlobalState,
+                CentralAppInvestorState, Prospectus,
+            },
+            dao_state_error::{DaoGlobalStateError, StateValueType},
+            dao_state_schema::{
+                keys_in_scope, StateScope, GLOBAL_IMAGE_ASSET_ID, GLOBAL_IMAGE_URL,
+                GLOBAL_PROSPECTUS_HASH, GLOBAL_PROSPECTUS_URL, GLOBAL_RAISED,
+                GLOBAL_SCHEMA_NUM_BYTE_SLICES, GLOBAL_SCHEMA_NUM_INTS, GLOBAL_TEAM_URL,
+            },
+        },
+        test_util::{self, capi_local_state},
+    };
+    use algonaut::{
+        core::Address,
+        model::algod::v2::{
+            Application, ApplicationLocalState, ApplicationParams, ApplicationStateSchema,
+        },
+    };
+    use anyhow::Result;
+    use rust_decimal::Decimal;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_global_state_round_trip() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+
+        let key_values = dao_global_state_to_key_values(&state)?;
+        let decoded = dao_global_state_from_key_values(&key_values, state.owner)?;
+
+        assert_eq!(state, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_round_trip_with_unset_optionals() -> Result<()> {
+        let state = CentralAppGlobalState {
+            project_desc_url: None,
+            image_nft: None,
+            prospectus: None,
+            team_url: None,
+            ..global_state_with_all_fields_set()?
+        };
+
+        let key_values = dao_global_state_to_key_values(&state)?;
+        let decoded = dao_global_state_from_key_values(&key_values, state.owner)?;
+
+        assert_eq!(state, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoded_global_state_matches_schema() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        assert_eq!(
+            (GLOBAL_SCHEMA_NUM_BYTE_SLICES + GLOBAL_SCHEMA_NUM_INTS) as usize,
+            key_values.len()
+        );
+        for spec in keys_in_scope(StateScope::Global) {
+            let kv = key_values
+                .iter()
+                .find(|kv| kv.key == spec.key.to_teal_encoded_str())
+                .unwrap_or_else(|| panic!("{:?} not encoded", spec.key));
+            let expected_type = match spec.value_type {
+                StateValueType::Uint => TEAL_VALUE_TYPE_UINT,
+                StateValueType::Bytes => TEAL_VALUE_TYPE_BYTES,
+            };
+            assert_eq!(expected_type, kv.value.value_type, "{:?}", spec.key);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_after_setup_round_trip() -> Result<()> {
+        let investors_share: Decimal = "0.4".parse().unwrap();
+        let specs = SetupDaoSpecs::new(
+            "my dao".to_owned(),
+            Some("https://desc".to_owned()),
+            CreateSharesSpecs {
+                token_name: "DAO".to_owned(),
+                supply: ShareAmount::new(1_000),
+            },
+            investors_share.try_into()?,
+            FundsAmount::new(5_000_000),
+            Some("https://image".to_owned()),
+            "https://social".to_owned(),
+            ShareAmount::new(600),
+            FundsAmount::new(1_000_000),
+            Timestamp(1_700_000_000),
+            Some(Prospectus::new(
+                b"prospectus",
+                "https://prospectus".to_owned(),
+            )),
+            ShareAmount::new(1),
+            ShareAmount::new(100),
+        )?;
+
+        let state = dao_global_state_after_setup(
+            &specs,
+            Address([1; 32]),
+            FundsAssetId(123),
+            456,
+            Some(789),
+            Versions {
+                app_approval: Version(1),
+                app_clear: Version(1),
+            },
+            Timestamp(1_600_000_000),
+        )?;
+
+        let key_values = dao_global_state_to_key_values(&state)?;
+        let decoded = dao_global_state_from_key_values(&key_values, state.owner)?;
+
+        assert_eq!(state, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_not_set_up_error() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        let res = dao_global_state_from_key_values(&key_values[0..5], state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::NotSetUp {
+                len: 5,
+                expected: key_values.len()
+            }),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_wrong_type_error() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        let raised_index = key_values
+            .iter()
+            .position(|kv| kv.key == GLOBAL_RAISED.to_teal_encoded_str())
+            .unwrap();
+        key_values[raised_index] = bytes_key_value(&GLOBAL_RAISED, vec![1, 2, 3]);
+
+        let res = dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::WrongType {
+                key: GLOBAL_RAISED,
+                expected: StateValueType::Uint,
+                actual: TEAL_VALUE_TYPE_BYTES
+            }),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_inconsistent_prospectus_error() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        let hash_index = key_values
+            .iter()
+            .position(|kv| kv.key == GLOBAL_PROSPECTUS_HASH.to_teal_encoded_str())
+            .unwrap();
+        key_values[hash_index] = bytes_key_value(&GLOBAL_PROSPECTUS_HASH, vec![]);
+
+        let res = dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::InconsistentPair(
+                GLOBAL_PROSPECTUS_URL,
+                GLOBAL_PROSPECTUS_HASH
+            )),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_inconsistent_image_nft_error() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        let url_index = key_values
+            .iter()
+            .position(|kv| kv.key == GLOBAL_IMAGE_URL.to_teal_encoded_str())
+            .unwrap();
+        key_values[url_index] = bytes_key_value(&GLOBAL_IMAGE_URL, vec![]);
+
+        let res = dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::InconsistentPair(
+                GLOBAL_IMAGE_ASSET_ID,
+                GLOBAL_IMAGE_URL
+            )),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_without_optional_key_is_decoded_as_unset() -> Result<()> {
+        let state = CentralAppGlobalState {
+            team_url: None,
+            ..global_state_with_all_fields_set()?
+        };
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        key_values.retain(|kv| kv.key != GLOBAL_TEAM_URL.to_teal_encoded_str());
+
+        let decoded = dao_global_state_from_key_values(&key_values, state.owner)?;
+
+        assert_eq!(state, decoded);
+
+        Ok(())
+    }
 
-    state_map.contains_key(&LOCAL_CLAIMED_TOTAL.to_teal_encoded_str())
-        && state_map.contains_key(&LOCAL_CLAIMED_INIT.to_teal_encoded_str())
-        && state_map.contains_key(&LOCAL_SHARES.to_teal_encoded_str())
+    #[test]
+    fn test_global_state_without_required_key_is_not_set_up() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        key_values.retain(|kv| kv.key != GLOBAL_RAISED.to_teal_encoded_str());
+
+        let res = dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert!(matches!(res, Err(DaoGlobalStateError::NotSetUp { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_global_state_reports_missing_keys() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        let partial = partial_dao_global_state_from_key_values(&key_values[0..5], state.owner)?;
+
+        assert!(!partial.setup_progress.is_complete());
+        assert_eq!(5, partial.setup_progress.present.len());
+        assert_eq!(key_values.len() - 5, partial.setup_progress.missing.len());
+        assert!(partial.setup_progress.unknown.is_empty());
+        assert_eq!(Some(state.received), partial.received);
+        assert_eq!(None, partial.raised);
+
+        let res: Result<CentralAppGlobalState, DaoGlobalStateError> = partial.try_into();
+        assert!(matches!(res, Err(DaoGlobalStateError::MissingKey(_, _))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_global_state_complete() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let mut key_values = dao_global_state_to_key_values(&state)?;
+        key_values.push(uint_key_value(&AppStateKey("NotInSchema"), 1));
+
+        let partial = partial_dao_global_state_from_key_values(&key_values, state.owner)?;
+
+        assert!(partial.setup_progress.is_complete());
+        assert_eq!(
+            vec!["NotInSchema".to_owned()],
+            partial.setup_progress.unknown
+        );
+
+        let decoded: CentralAppGlobalState = partial.try_into()?;
+        assert_eq!(state, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_decoded_with_stored_version_layout() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        let (decoded, layout) =
+            dao_global_state_and_layout_from_key_values(&key_values, state.owner)?;
+
+        assert_eq!(state, decoded);
+        assert_eq!(state.app_approval_version, layout.version);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_unsupported_version_error() -> Result<()> {
+        let mut state = global_state_with_all_fields_set()?;
+        state.app_approval_version = Version(9999);
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        let res = dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::UnsupportedVersion(Version(9999))),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partial_global_state_unsupported_version_error() -> Result<()> {
+        let mut state = global_state_with_all_fields_set()?;
+        state.app_approval_version = Version(9999);
+        let key_values = dao_global_state_to_key_values(&state)?;
+
+        // the setup progress can't be determined without the version's layout
+        let res = partial_dao_global_state_from_key_values(&key_values, state.owner);
+
+        assert_eq!(
+            Err(DaoGlobalStateError::UnsupportedVersion(Version(9999))),
+            res
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_state_decoded_with_version_layout() -> Result<()> {
+        let investor_state = CentralAppInvestorState {
+            shares: ShareAmount::new(10),
+            claimed: FundsAmount::new(20),
+            claimed_init: FundsAmount::new(5),
+            signed_prospectus: None,
+        };
+        let key_values = investor_state.to_key_values_for_tests();
+
+        assert_eq!(
+            investor_state,
+            central_investor_state_from_key_values(&key_values, Version(1))?
+        );
+        assert_eq!(
+            Err(ApplicationLocalStateError::InvalidState(
+                DaoGlobalStateError::UnsupportedVersion(Version(9999))
+            )),
+            central_investor_state_from_key_values(&key_values, Version(9999))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_capi_local_state_of_version() {
+        let local_state = capi_local_state(123, &investor_state(10));
+
+        assert!(matches_capi_local_state(&local_state, Version(1)));
+        // no layout for the version
+        assert!(!matches_capi_local_state(&local_state, Version(9999)));
+    }
+
+    #[test]
+    fn test_portfolio_with_several_daos() -> Result<()> {
+        let state1 = investor_state(10);
+        let state2 = investor_state(25);
+        let local_states = vec![capi_local_state(1, &state1), capi_local_state(2, &state2)];
+        let apps = vec![dao_app(1)?, dao_app(2)?];
+
+        let portfolio = investor_portfolio_from_local_states(&local_states, &apps);
+
+        assert_eq!(
+            vec![(DaoAppId(1), state1), (DaoAppId(2), state2)],
+            portfolio
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_portfolio_includes_opted_in_dao_without_shares() -> Result<()> {
+        // e.g. opted in but didn't invest yet, or unlocked all the shares
+        let state = investor_state(0);
+        let local_states = vec![capi_local_state(1, &state)];
+
+        let portfolio = investor_portfolio_from_local_states(&local_states, &[dao_app(1)?]);
+
+        assert_eq!(vec![(DaoAppId(1), state)], portfolio);
+        Ok(())
+    }
+
+    #[test]
+    fn test_portfolio_skips_foreign_apps() -> Result<()> {
+        let state = investor_state(10);
+        let foreign_local_state = ApplicationLocalState {
+            id: 2,
+            key_value: vec![uint_key_value(&AppStateKey("Score"), 5)],
+            schema: ApplicationStateSchema {
+                num_byte_slice: 0,
+                num_uint: 1,
+            },
+        };
+        let foreign_app = Application {
+            id: 2,
+            params: ApplicationParams {
+                global_state: vec![],
+                ..dao_app(2)?.params
+            },
+        };
+        let local_states = vec![capi_local_state(1, &state), foreign_local_state];
+
+        let portfolio =
+            investor_portfolio_from_local_states(&local_states, &[dao_app(1)?, foreign_app]);
+
+        assert_eq!(vec![(DaoAppId(1), state)], portfolio);
+        Ok(())
+    }
+
+    #[test]
+    fn test_portfolio_skips_local_state_without_app() -> Result<()> {
+        // e.g. the app was deleted, so it couldn't be fetched
+        let local_states = vec![capi_local_state(1, &investor_state(10))];
+
+        let portfolio = investor_portfolio_from_local_states(&local_states, &[]);
+
+        assert!(portfolio.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_apps_skips_deleted_and_foreign_apps() -> Result<()> {
+        let client = FakeChainClient::new();
+        client.add_app(dao_app(1)?);
+        // foreign local state schema: the app isn't fetched (it would fail)
+        client.fail_app(3, "node error");
+        let local_states = vec![
+            capi_local_state(1, &investor_state(10)),
+            // deleted app
+            capi_local_state(2, &investor_state(10)),
+            ApplicationLocalState {
+                schema: ApplicationStateSchema {
+                    num_byte_slice: 0,
+                    num_uint: 1,
+                },
+                ..capi_local_state(3, &investor_state(10))
+            },
+        ];
+
+        let apps = portfolio_apps(&client, &local_states, 2).await?;
+
+        assert_eq!(vec![1], apps.iter().map(|app| app.id).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_portfolio_apps_fails_if_an_app_fails() -> Result<()> {
+        let client = FakeChainClient::new();
+        client.add_app(dao_app(1)?);
+        client.fail_app(2, "node error");
+        let local_states = vec![
+            capi_local_state(1, &investor_state(10)),
+            capi_local_state(2, &investor_state(10)),
+        ];
+
+        let res = portfolio_apps(&client, &local_states, 2).await;
+
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    fn investor_state(shares: u64) -> CentralAppInvestorState {
+        CentralAppInvestorState {
+            shares: ShareAmount::new(shares),
+            claimed: FundsAmount::new(0),
+            claimed_init: FundsAmount::new(0),
+            signed_prospectus: None,
+        }
+    }
+
+    fn dao_app(app_id: u64) -> Result<Application> {
+        test_util::dao_app(app_id, &CentralAppGlobalState::for_tests())
+    }
+
+    #[tokio::test]
+    async fn test_dao_global_state_from_client() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let client = FakeChainClient::new();
+        client.add_app_with_global_state(123, state.owner, dao_global_state_to_key_values(&state)?);
+
+        let fetched = dao_global_state(&client, DaoAppId(123)).await?;
+
+        assert_eq!(state, fetched);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dao_global_states_reports_errors_per_dao() -> Result<()> {
+        let state = global_state_with_all_fields_set()?;
+        let client = FakeChainClient::new();
+        client.add_app_with_global_state(1, state.owner, dao_global_state_to_key_values(&state)?);
+        client.add_app_with_global_state(3, state.owner, dao_global_state_to_key_values(&state)?);
+
+        let app_ids = [DaoAppId(1), DaoAppId(2), DaoAppId(3)];
+        let states = dao_global_states(&client, &app_ids, 2).await;
+
+        assert_eq!(3, states.len());
+        assert_eq!((DaoAppId(1), Ok(state.clone())), states[0]);
+        assert_eq!(DaoAppId(2), states[1].0);
+        assert!(matches!(states[1].1, Err(DaoGlobalStateError::Network(_))));
+        assert_eq!((DaoAppId(3), Ok(state)), states[2]);
+
+        Ok(())
+    }
+
+    fn global_state_with_all_fields_set() -> Result<CentralAppGlobalState> {
+        let investors_share: Decimal = "0.35".parse().unwrap();
+        Ok(CentralAppGlobalState {
+            received: FundsAmount::new(10_000),
+            available: FundsAmount::new(2_000),
+            app_clear_version: Version(2),
+            project_desc_url: Some("https://desc".to_owned()),
+            share_price: FundsAmount::new(5_000_000),
+            investors_share: investors_share.try_into()?,
+            image_nft: Some(Nft {
+                url: "https://image".to_owned(),
+                asset_id: 789,
+            }),
+            prospectus: Some(Prospectus::new(
+                b"prospectus",
+                "https://prospectus".to_owned(),
+            )),
+            locked_shares: ShareAmount::new(20),
+            min_funds_target: FundsAmount::new(1_000_000),
+            min_funds_target_end_date: Timestamp(1_700_000_000),
+            raised: FundsAmount::new(3_000_000),
+            setup_date: Timestamp(1_600_000_000),
+            team_url: Some("https://team".to_owned()),
+            ..CentralAppGlobalState::for_tests()
+        })
+    }
 }
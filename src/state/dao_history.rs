@@ -0,0 +1,541 @@
+use super::dao_app_state::CentralAppGlobalState;
+use crate::{
+    api::dao_app_call::DaoAppCall,
+    chain::indexer_client::{IndexedTx, IndexedTxKind, IndexerClient},
+    models::{
+        dao_app_id::DaoAppId, funds::FundsAmount, share_amount::ShareAmount, timestamp::Timestamp,
+        tx_id::TxId,
+    },
+};
+use algonaut::core::Address;
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaoEventKind {
+    Invest {
+        shares: ShareAmount,
+        paid: FundsAmount,
+    },
+    Lock {
+        shares: ShareAmount,
+    },
+    Unlock {
+        shares: ShareAmount,
+    },
+    Claim {
+        amount: FundsAmount,
+    },
+    Withdraw {
+        amount: FundsAmount,
+    },
+    /// Made the received funds available. The fee is the capi fee paid in the drain.
+    Drain {
+        capi_fee: FundsAmount,
+    },
+    /// Funds sent to the DAO outside of the app flows (e.g. customer payments), to be drained.
+    Income {
+        amount: FundsAmount,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoEvent {
+    pub kind: DaoEventKind,
+    pub sender: Address,
+    pub tx_id: TxId,
+    pub date: Timestamp,
+    pub round: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoHistoryPage {
+    pub events: Vec<DaoEvent>,
+    /// Pass to [dao_history] to get the next page. None if there are no more events.
+    pub next_token: Option<String>,
+}
+
+/// Events of the DAO, classified from the transactions involving the app address.
+/// `limit` is the max number of transactions per page - there can be less events, as not all transactions are events.
+///
+/// Invest and lock are detected by the transfers to the app, classified by the app call of their group,
+/// claim, withdraw, unlock and drain by the app calls (whose inner transactions transfer from the app).
+/// Funds transfers that aren't part of an app call group are income.
+pub async fn dao_history(
+    indexer: &impl IndexerClient,
+    app_id: DaoAppId,
+    dao: &CentralAppGlobalState,
+    next_token: Option<String>,
+    limit: u64,
+) -> Result<DaoHistoryPage> {
+    let app_address = app_id.address();
+    let page = indexer
+        .txs_with_address(&app_address, next_token, limit)
+        .await?;
+
+    let mut events = vec![];
+    for tx in &page.txs {
+        if let Some(kind) = to_event_kind(indexer, tx, app_id, &app_address, dao).await? {
+            events.push(DaoEvent {
+                kind,
+                sender: tx.sender,
+                tx_id: tx
+                    .id
+                    .clone()
+                    .ok_or_else(|| anyhow!("Unexpected: indexer tx without id: {tx:?}"))?,
+                date: tx
+                    .timestamp
+                    .ok_or_else(|| anyhow!("Unexpected: indexer tx without timestamp: {tx:?}"))?,
+                round: confirmed_round(tx)?,
+            });
+        }
+    }
+
+    Ok(DaoHistoryPage {
+        events,
+        next_token: page.next_token,
+    })
+}
+
+async fn to_event_kind(
+    indexer: &impl IndexerClient,
+    tx: &IndexedTx,
+    app_id: DaoAppId,
+    app_address: &Address,
+    dao: &CentralAppGlobalState,
+) -> Result<Option<DaoEventKind>> {
+    Ok(match &tx.kind {
+        IndexedTxKind::AppCall {
+            app_id: called_app_id,
+            args,
+        } if *called_app_id == app_id.0 => {
+            let funds_sent = FundsAmount::new(sent_by_app(tx, app_address, dao.funds_asset_id.0));
+            match app_call_of(tx, args)? {
+                Some(DaoAppCall::Claim) => Some(DaoEventKind::Claim { amount: funds_sent }),
+                Some(DaoAppCall::Withdraw) => Some(DaoEventKind::Withdraw { amount: funds_sent }),
+                Some(DaoAppCall::Drain) => Some(DaoEventKind::Drain {
+                    capi_fee: funds_sent,
+                }),
+                Some(DaoAppCall::Unlock) => Some(DaoEventKind::Unlock {
+                    shares: ShareAmount::new(sent_by_app(tx, app_address, dao.shares_asset_id)),
+                }),
+                // invest and lock are detected with their transfers
+                _ => None,
+            }
+        }
+        IndexedTxKind::AssetTransfer {
+            asset_id,
+            amount,
+            receiver,
+        } if receiver == app_address => {
+            let group_call = group_app_call(indexer, tx, app_id).await?;
+            if *asset_id == dao.funds_asset_id.0 {
+                match group_call {
+                    Some(DaoAppCall::Invest) => Some(DaoEventKind::Invest {
+                        shares: ShareAmount::new(shares_bought(*amount, dao.share_price)?),
+                        paid: FundsAmount::new(*amount),
+                    }),
+                    // not grouped with an app call (e.g. a customer payment, grouped or not)
+                    None => Some(DaoEventKind::Income {
+                        amount: FundsAmount::new(*amount),
+                    }),
+                    // part of another app flow (e.g. setup)
+                    Some(_) => None,
+                }
+            } else if *asset_id == dao.shares_asset_id && group_call == Some(DaoAppCall::Lock) {
+                Some(DaoEventKind::Lock {
+                    shares: ShareAmount::new(*amount),
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
+
+/// The call to our app in the group of the tx, if any
+async fn group_app_call(
+    indexer: &impl IndexerClient,
+    tx: &IndexedTx,
+    app_id: DaoAppId,
+) -> Result<Option<DaoAppCall>> {
+    let group = match &tx.group {
+        Some(group) => group,
+        None => return Ok(None),
+    };
+    for group_tx in indexer.group_txs(confirmed_round(tx)?, group).await? {
+        if let IndexedTxKind::AppCall {
+            app_id: called_app_id,
+            args,
+        } = &group_tx.kind
+        {
+            if *called_app_id == app_id.0 {
+                return app_call_of(&group_tx, args);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Set on the top level txs (the ones we classify)
+fn confirmed_round(tx: &IndexedTx) -> Result<u64> {
+    tx.round
+        .ok_or_else(|| anyhow!("Unexpected: indexer tx without round: {tx:?}"))
+}
+
+/// Errors if the first arg isn't a branch of our app: we'd misclassify the transactions otherwise
+fn app_call_of(tx: &IndexedTx, args: &[Vec<u8>]) -> Result<Option<DaoAppCall>> {
+    match args.first() {
+        Some(arg) => DaoAppCall::from_arg(arg).map(Some).ok_or_else(|| {
+            anyhow!(
+                "Unknown app call arg: {:?} in tx: {:?}",
+                String::from_utf8_lossy(arg),
+                tx.id
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// The app only accepts payments for whole shares, so a remainder means that we misclassified the payment
+fn shares_bought(paid: u64, share_price: FundsAmount) -> Result<u64> {
+    let price = share_price.val();
+    match paid.checked_rem(price) {
+        Some(0) => Ok(paid / price),
+        Some(_) => Err(anyhow!(
+            "Invest payment: {paid} isn't a multiple of the share price: {price}"
+        )),
+        None => Err(anyhow!("Invalid state: share price is 0")),
+    }
+}
+
+/// Sum of the asset transferred by the app in the inner transactions
+fn sent_by_app(tx: &IndexedTx, app_address: &Address, asset: u64) -> u64 {
+    tx.inner_txs
+        .iter()
+        .filter(|inner| &inner.sender == app_address)
+        .map(|inner| match inner.kind {
+            IndexedTxKind::AssetTransfer {
+                asset_id, amount, ..
+            } if asset_id == asset => amount,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::dao_app_call::DaoAppCall,
+        chain::{
+            fake_indexer_client::FakeIndexerClient,
+            indexer_client::{IndexedTx, IndexedTxKind},
+        },
+        models::{
+            dao_app_id::DaoAppId,
+            funds::{FundsAmount, FundsAssetId},
+            share_amount::ShareAmount,
+            timestamp::Timestamp,
+            tx_id::TxId,
+        },
+        state::{
+            dao_app_state::CentralAppGlobalState,
+            dao_history::{dao_history, DaoEventKind},
+        },
+    };
+    use algonaut::{core::Address, crypto::HashDigest};
+    use anyhow::Result;
+
+    const APP_ID: DaoAppId = DaoAppId(123);
+
+    #[tokio::test]
+    async fn test_dao_history() -> Result<()> {
+        let app_address = APP_ID.address();
+        let dao = dao_state();
+        let investor = Address([2; 32]);
+        let customer = Address([3; 32]);
+
+        let indexer = FakeIndexerClient::new();
+        // customer payment
+        indexer.add_tx(tx(
+            1,
+            customer,
+            None,
+            funds_transfer(500, app_address),
+            vec![],
+        ));
+        // invest: the app call isn't returned for the app address, but the payment is
+        indexer.add_tx(tx(
+            2,
+            investor,
+            Some("group"),
+            funds_transfer(100, app_address),
+            vec![],
+        ));
+        indexer.add_tx(in_round(
+            2,
+            tx(
+                3,
+                investor,
+                Some("group"),
+                app_call(DaoAppCall::Invest),
+                vec![],
+            ),
+        ));
+        // claim
+        indexer.add_tx(tx(
+            4,
+            investor,
+            None,
+            app_call(DaoAppCall::Claim),
+            vec![inner_tx(app_address, funds_transfer(40, investor))],
+        ));
+
+        let page = dao_history(&indexer, APP_ID, &dao, None, 2).await?;
+        let kinds: Vec<DaoEventKind> = page.events.into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            vec![
+                DaoEventKind::Income {
+                    amount: FundsAmount::new(500)
+                },
+                DaoEventKind::Invest {
+                    shares: ShareAmount::new(10),
+                    paid: FundsAmount::new(100)
+                },
+            ],
+            kinds
+        );
+        assert!(page.next_token.is_some());
+
+        let page = dao_history(&indexer, APP_ID, &dao, page.next_token, 2).await?;
+        assert_eq!(1, page.events.len());
+        assert_eq!(
+            DaoEventKind::Claim {
+                amount: FundsAmount::new(40)
+            },
+            page.events[0].kind
+        );
+        assert_eq!(investor, page.events[0].sender);
+        assert_eq!(None, page.next_token);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_grouped_payment_is_income() -> Result<()> {
+        let customer = Address([3; 32]);
+        let indexer = FakeIndexerClient::new();
+        // e.g. a wallet that groups the payment with a fee payment
+        indexer.add_tx(tx(
+            1,
+            customer,
+            Some("group"),
+            funds_transfer(500, APP_ID.address()),
+            vec![],
+        ));
+        indexer.add_tx(in_round(
+            1,
+            tx(
+                2,
+                customer,
+                Some("group"),
+                funds_transfer(1, Address([4; 32])),
+                vec![],
+            ),
+        ));
+
+        let page = dao_history(&indexer, APP_ID, &dao_state(), None, 10).await?;
+
+        let kinds: Vec<DaoEventKind> = page.events.into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            vec![DaoEventKind::Income {
+                amount: FundsAmount::new(500)
+            }],
+            kinds
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lock_needs_lock_call() -> Result<()> {
+        let investor = Address([2; 32]);
+        let indexer = FakeIndexerClient::new();
+        indexer.add_tx(tx(
+            1,
+            investor,
+            Some("lock"),
+            shares_transfer(5, APP_ID.address()),
+            vec![],
+        ));
+        indexer.add_tx(in_round(
+            1,
+            tx(
+                2,
+                investor,
+                Some("lock"),
+                app_call(DaoAppCall::Lock),
+                vec![],
+            ),
+        ));
+        // shares sent to the app outside of a lock
+        indexer.add_tx(tx(
+            3,
+            investor,
+            None,
+            shares_transfer(7, APP_ID.address()),
+            vec![],
+        ));
+
+        let page = dao_history(&indexer, APP_ID, &dao_state(), None, 10).await?;
+
+        let kinds: Vec<DaoEventKind> = page.events.into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            vec![DaoEventKind::Lock {
+                shares: ShareAmount::new(5)
+            }],
+            kinds
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_app_call_arg_is_an_error() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        indexer.add_tx(tx(
+            1,
+            Address([2; 32]),
+            None,
+            IndexedTxKind::AppCall {
+                app_id: APP_ID.0,
+                args: vec![b"not_a_call".to_vec()],
+            },
+            vec![inner_tx(
+                APP_ID.address(),
+                funds_transfer(40, Address([2; 32])),
+            )],
+        ));
+
+        let res = dao_history(&indexer, APP_ID, &dao_state(), None, 10).await;
+
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_without_round_is_an_error() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        indexer.add_tx(IndexedTx {
+            round: None,
+            ..tx(
+                1,
+                Address([2; 32]),
+                None,
+                funds_transfer(10, APP_ID.address()),
+                vec![],
+            )
+        });
+
+        let res = dao_history(&indexer, APP_ID, &dao_state(), None, 10).await;
+
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invest_payment_not_multiple_of_price_is_an_error() -> Result<()> {
+        let investor = Address([2; 32]);
+        let indexer = FakeIndexerClient::new();
+        indexer.add_tx(tx(
+            1,
+            investor,
+            Some("group"),
+            funds_transfer(105, APP_ID.address()),
+            vec![],
+        ));
+        indexer.add_tx(in_round(
+            1,
+            tx(
+                2,
+                investor,
+                Some("group"),
+                app_call(DaoAppCall::Invest),
+                vec![],
+            ),
+        ));
+
+        let res = dao_history(&indexer, APP_ID, &dao_state(), None, 10).await;
+
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    fn tx(
+        id: u8,
+        sender: Address,
+        group: Option<&str>,
+        kind: IndexedTxKind,
+        inner_txs: Vec<IndexedTx>,
+    ) -> IndexedTx {
+        IndexedTx {
+            id: Some(TxId::from(HashDigest([id; 32]))),
+            sender,
+            round: Some(id as u64),
+            timestamp: Some(Timestamp(1_000 + id as u64)),
+            group: group.map(|g| g.to_owned()),
+            kind,
+            inner_txs,
+        }
+    }
+
+    /// Inner txs don't have id, round and timestamp
+    fn inner_tx(sender: Address, kind: IndexedTxKind) -> IndexedTx {
+        IndexedTx {
+            id: None,
+            sender,
+            round: None,
+            timestamp: None,
+            group: None,
+            kind,
+            inner_txs: vec![],
+        }
+    }
+
+    /// Txs of a group have to be in the same round
+    fn in_round(round: u64, tx: IndexedTx) -> IndexedTx {
+        IndexedTx {
+            round: Some(round),
+            ..tx
+        }
+    }
+
+    fn app_call(call: DaoAppCall) -> IndexedTxKind {
+        IndexedTxKind::AppCall {
+            app_id: APP_ID.0,
+            args: vec![call.arg().to_vec()],
+        }
+    }
+
+    fn funds_transfer(amount: u64, receiver: Address) -> IndexedTxKind {
+        IndexedTxKind::AssetTransfer {
+            asset_id: 1,
+            amount,
+            receiver,
+        }
+    }
+
+    fn shares_transfer(amount: u64, receiver: Address) -> IndexedTxKind {
+        IndexedTxKind::AssetTransfer {
+            asset_id: 2,
+            amount,
+            receiver,
+        }
+    }
+
+    fn dao_state() -> CentralAppGlobalState {
+        CentralAppGlobalState {
+            funds_asset_id: FundsAssetId(1),
+            shares_asset_id: 2,
+            ..CentralAppGlobalState::for_tests()
+        }
+    }
+}
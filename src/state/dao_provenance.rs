@@ -0,0 +1,272 @@
+use super::{
+    app_state::{ApplicationGlobalState, ApplicationStateExt},
+    dao_app_state::{matches_capi_local_state, stored_state_layout},
+    dao_state_schema::GLOBAL_VERSIONS,
+};
+use crate::{
+    api::{
+        contract::{Contract, ContractRenderer},
+        teal_api::TealTemplateLoader,
+        version::{bytes_to_versions, Version},
+    },
+    chain::chain_client::ChainClient,
+    models::dao_app_id::DaoAppId,
+};
+use algonaut::model::algod::v2::{Application, ApplicationLocalState};
+use anyhow::Result;
+
+/// How confident we are that an app is a capi DAO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvenanceVerdict {
+    /// The app's programs are the ones we render for its stored versions
+    Verified,
+    /// The local state looks like capi's, but the programs couldn't be checked (versions not set, i.e. not set up yet)
+    SchemaOnly,
+    /// The local state or the programs don't match capi's.
+    /// Also if the stored versions have no templates: the versions are set by the app, so they can't be trusted.
+    Mismatch,
+}
+
+/// Stronger alternative to [matches_capi_local_state]: additionally compares the app's programs
+/// with the programs rendered from the templates of the versions stored in the app's global state.
+pub async fn verify_dao_provenance(
+    algod: &impl ChainClient,
+    local_state: &ApplicationLocalState,
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+) -> Result<ProvenanceVerdict> {
+    let app = algod.application_information(local_state.id).await?;
+    // the local state is checked against the layout of the app's stored version
+    match stored_state_layout(&ApplicationGlobalState(app.params.global_state.clone())) {
+        Ok(layout) => {
+            if !matches_capi_local_state(local_state, layout.version) {
+                return Ok(ProvenanceVerdict::Mismatch);
+            }
+        }
+        // includes unknown versions: otherwise any app could avoid the checks by storing e.g. version 9999
+        Err(_) => return Ok(ProvenanceVerdict::Mismatch),
+    }
+    verify_app_programs(algod, &app, loader, renderer).await
+}
+
+/// Like [verify_dao_provenance], without the local state check (e.g. to check a DAO the user isn't opted in to).
+pub async fn verify_dao_app_programs(
+    algod: &impl ChainClient,
+    app_id: DaoAppId,
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+) -> Result<ProvenanceVerdict> {
+    let app = algod.application_information(app_id.0).await?;
+    verify_app_programs(algod, &app, loader, renderer).await
+}
+
+async fn verify_app_programs(
+    algod: &impl ChainClient,
+    app: &Application,
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+) -> Result<ProvenanceVerdict> {
+    let global_state = ApplicationGlobalState(app.params.global_state.clone());
+    let versions = match global_state
+        .find_bytes(&GLOBAL_VERSIONS)
+        .and_then(|bytes| bytes_to_versions(&bytes).ok())
+    {
+        Some(versions) => versions,
+        // not set up yet, or not a capi app
+        None => return Ok(ProvenanceVerdict::SchemaOnly),
+    };
+
+    let approval = expected_program(
+        algod,
+        loader,
+        renderer,
+        Contract::DaoAppApproval,
+        versions.app_approval,
+    )
+    .await?;
+    let clear = expected_program(
+        algod,
+        loader,
+        renderer,
+        Contract::DaoAppClear,
+        versions.app_clear,
+    )
+    .await?;
+
+    Ok(match (approval, clear) {
+        (Some(approval), Some(clear)) => {
+            if approval == app.params.approval_program && clear == app.params.clear_state_program {
+                ProvenanceVerdict::Verified
+            } else {
+                ProvenanceVerdict::Mismatch
+            }
+        }
+        // no templates for the stored versions: nothing we rendered can be behind this app
+        _ => ProvenanceVerdict::Mismatch,
+    })
+}
+
+/// Compiled program for the contract version, None if there's no template for it
+async fn expected_program(
+    algod: &impl ChainClient,
+    loader: &impl TealTemplateLoader,
+    renderer: &impl ContractRenderer,
+    contract: Contract,
+    version: Version,
+) -> Result<Option<Vec<u8>>> {
+    match loader.template(contract, version)? {
+        Some(template) => {
+            let source = renderer.render(contract, &template)?;
+            Ok(Some(algod.teal_compile(&source.0).await?.0))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::version::{versions_to_bytes, Version, Versions},
+        chain::fake_chain_client::FakeChainClient,
+        models::{dao_app_id::DaoAppId, funds::FundsAmount, share_amount::ShareAmount},
+        state::{
+            app_state::bytes_key_value,
+            dao_app_state::CentralAppInvestorState,
+            dao_provenance::{verify_dao_app_programs, verify_dao_provenance, ProvenanceVerdict},
+            dao_state_schema::GLOBAL_VERSIONS,
+        },
+        test_util::{capi_local_state, InMemoryLoader, PassthroughRenderer},
+    };
+    use algonaut::{
+        core::{Address, CompiledTeal},
+        model::algod::v2::{Application, ApplicationParams},
+    };
+    use anyhow::Result;
+
+    const APP_ID: DaoAppId = DaoAppId(123);
+
+    #[tokio::test]
+    async fn test_matching_programs_are_verified() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        algod.add_app(app(Version(1), b"approval v1", b"clear v1")?);
+
+        let verdict =
+            verify_dao_app_programs(&algod, APP_ID, &InMemoryLoader::v1(), &PassthroughRenderer)
+                .await?;
+
+        assert_eq!(ProvenanceVerdict::Verified, verdict);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_provenance_of_capi_local_state_with_matching_programs() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        algod.add_app(app(Version(1), b"approval v1", b"clear v1")?);
+
+        let verdict = verify_dao_provenance(
+            &algod,
+            &capi_local_state(APP_ID.0, &investor_state()),
+            &InMemoryLoader::v1(),
+            &PassthroughRenderer,
+        )
+        .await?;
+
+        assert_eq!(ProvenanceVerdict::Verified, verdict);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_provenance_with_unknown_stored_version_is_a_mismatch() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        // e.g. an app imitating the local state, with programs we can't check
+        algod.add_app(app(Version(9999), b"approval v1", b"clear v1")?);
+
+        let verdict = verify_dao_provenance(
+            &algod,
+            &capi_local_state(APP_ID.0, &investor_state()),
+            &InMemoryLoader::v1(),
+            &PassthroughRenderer,
+        )
+        .await?;
+
+        assert_eq!(ProvenanceVerdict::Mismatch, verdict);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tampered_approval_is_a_mismatch() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        algod.add_app(app(Version(1), b"approval v1 tampered", b"clear v1")?);
+
+        let verdict =
+            verify_dao_app_programs(&algod, APP_ID, &InMemoryLoader::v1(), &PassthroughRenderer)
+                .await?;
+
+        assert_eq!(ProvenanceVerdict::Mismatch, verdict);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tampered_clear_is_a_mismatch() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        algod.add_app(app(Version(1), b"approval v1", b"clear v1 tampered")?);
+
+        let verdict =
+            verify_dao_app_programs(&algod, APP_ID, &InMemoryLoader::v1(), &PassthroughRenderer)
+                .await?;
+
+        assert_eq!(ProvenanceVerdict::Mismatch, verdict);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_version_is_a_mismatch() -> Result<()> {
+        let algod = chain_with_compiled_programs();
+        algod.add_app(app(Version(2), b"approval v2", b"clear v1")?);
+
+        let verdict =
+            verify_dao_app_programs(&algod, APP_ID, &InMemoryLoader::v1(), &PassthroughRenderer)
+                .await?;
+
+        assert_eq!(ProvenanceVerdict::Mismatch, verdict);
+        Ok(())
+    }
+
+    fn investor_state() -> CentralAppInvestorState {
+        CentralAppInvestorState {
+            shares: ShareAmount::new(10),
+            claimed: FundsAmount::new(0),
+            claimed_init: FundsAmount::new(0),
+            signed_prospectus: None,
+        }
+    }
+
+    /// "Compiles" the v1 templates
+    fn chain_with_compiled_programs() -> FakeChainClient {
+        let algod = FakeChainClient::new();
+        algod.add_compiled_teal(b"DaoAppApproval v1", CompiledTeal(b"approval v1".to_vec()));
+        algod.add_compiled_teal(b"DaoAppClear v1", CompiledTeal(b"clear v1".to_vec()));
+        algod
+    }
+
+    fn app(approval_version: Version, approval: &[u8], clear: &[u8]) -> Result<Application> {
+        let versions = Versions {
+            app_approval: approval_version,
+            app_clear: Version(1),
+        };
+        Ok(Application {
+            id: APP_ID.0,
+            params: ApplicationParams {
+                approval_program: approval.to_vec(),
+                clear_state_program: clear.to_vec(),
+                creator: Address([1; 32]),
+                global_state: vec![bytes_key_value(
+                    &GLOBAL_VERSIONS,
+                    versions_to_bytes(versions)?,
+                )],
+                global_state_schema: None,
+                local_state_schema: None,
+            },
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use super::app_state::AppStateKey;
+use crate::api::version::Version;
+use algonaut::error::ServiceError;
+use std::fmt::{self, Display, Formatter};
+
+/// Type of a value in TEAL state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateValueType {
+    Uint,
+    Bytes,
+}
+
+/// Errors decoding DAO state (global and investor's local state)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaoGlobalStateError {
+    /// The global state has less entries than expected - the DAO setup (branch_setup_dao) hasn't been performed yet.
+    NotSetUp {
+        len: usize,
+        expected: usize,
+    },
+    /// The state has a different number of entries than expected.
+    UnexpectedLength {
+        len: usize,
+        expected: usize,
+    },
+    MissingKey(AppStateKey<'static>, StateValueType),
+    WrongType {
+        key: AppStateKey<'static>,
+        expected: StateValueType,
+        actual: u64,
+    },
+    InvalidUtf8(AppStateKey<'static>),
+    /// Values that have to be set together (e.g. nft asset id and url), where only one is set.
+    InconsistentPair(AppStateKey<'static>, AppStateKey<'static>),
+    BadVersionsEncoding(Vec<u8>),
+    /// There's no global state layout registered for the app approval version stored in the state.
+    UnsupportedVersion(Version),
+    /// The value has the expected type but isn't valid for the domain (e.g. a percentage > 1)
+    /// Covers single values only: type mismatches are [Self::WrongType], values that conflict with another key [Self::InconsistentPair].
+    InvalidValue(AppStateKey<'static>, String),
+    /// The historical state couldn't be rebuilt from the app calls (e.g. calls before the app creation)
+    InvalidHistory(String),
+    /// Error fetching the state
+    Network(String),
+}
+
+impl Display for DaoGlobalStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DaoGlobalStateError::NotSetUp { len, expected } => write!(
+                f,
+                "Unexpected global state length: {len}. Expected: {expected}. Was the DAO setup performed already?"
+            ),
+            DaoGlobalStateError::UnexpectedLength { len, expected } => {
+                write!(f, "Unexpected state length: {len}. Expected: {expected}")
+            }
+            DaoGlobalStateError::MissingKey(key, value_type) => {
+                write!(f, "Key: {key:?} ({value_type:?}) not set in state")
+            }
+            DaoGlobalStateError::WrongType {
+                key,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Key: {key:?} has wrong type: {actual}. Expected: {expected:?}"
+            ),
+            DaoGlobalStateError::InvalidUtf8(key) => {
+                write!(f, "Key: {key:?} value isn't valid utf-8")
+            }
+            DaoGlobalStateError::InconsistentPair(key1, key2) => write!(
+                f,
+                "Invalid state: {key1:?} and {key2:?} must both be set or not set"
+            ),
+            DaoGlobalStateError::BadVersionsEncoding(bytes) => {
+                write!(f, "Invalid versions encoding: {bytes:?}")
+            }
+            DaoGlobalStateError::UnsupportedVersion(version) => {
+                write!(f, "No global state layout for app approval version: {version:?}")
+            }
+            DaoGlobalStateError::InvalidValue(key, msg) => {
+                write!(f, "Key: {key:?} has invalid value: {msg}")
+            }
+            DaoGlobalStateError::InvalidHistory(msg) => {
+                write!(f, "Couldn't rebuild historical state: {msg}")
+            }
+            DaoGlobalStateError::Network(msg) => write!(f, "Error fetching state: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DaoGlobalStateError {}
+
+impl From<ServiceError> for DaoGlobalStateError {
+    fn from(e: ServiceError) -> Self {
+        Self::Network(e.to_string())
+    }
+}
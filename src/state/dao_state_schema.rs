@@ -0,0 +1,179 @@
+use super::{app_state::AppStateKey, dao_state_error::StateValueType};
+
+pub const GLOBAL_TOTAL_RECEIVED: AppStateKey = AppStateKey("CentralReceivedTotal");
+pub const GLOBAL_WITHDRAWABLE_AMOUNT: AppStateKey = AppStateKey("AvailableAmount");
+
+pub const GLOBAL_FUNDS_ASSET_ID: AppStateKey = AppStateKey("FundsAssetId");
+pub const GLOBAL_SHARES_ASSET_ID: AppStateKey = AppStateKey("SharesAssetId");
+
+pub const GLOBAL_DAO_NAME: AppStateKey = AppStateKey("DaoName");
+pub const GLOBAL_DAO_DESC: AppStateKey = AppStateKey("DaoDesc");
+pub const GLOBAL_SHARE_PRICE: AppStateKey = AppStateKey("SharePrice");
+pub const GLOBAL_INVESTORS_SHARE: AppStateKey = AppStateKey("InvestorsPart");
+
+pub const GLOBAL_IMAGE_URL: AppStateKey = AppStateKey("ImageUrl");
+pub const GLOBAL_IMAGE_ASSET_ID: AppStateKey = AppStateKey("ImageAsset");
+pub const GLOBAL_SOCIAL_MEDIA_URL: AppStateKey = AppStateKey("SocialMediaUrl");
+pub const GLOBAL_PROSPECTUS_URL: AppStateKey = AppStateKey("ProspectusUrl");
+pub const GLOBAL_PROSPECTUS_HASH: AppStateKey = AppStateKey("ProspectusHash");
+
+pub const GLOBAL_SHARES_LOCKED: AppStateKey = AppStateKey("LockedShares");
+
+pub const GLOBAL_VERSIONS: AppStateKey = AppStateKey("Versions");
+
+pub const GLOBAL_TARGET: AppStateKey = AppStateKey("Target");
+pub const GLOBAL_TARGET_END_DATE: AppStateKey = AppStateKey("TargetEndDate");
+pub const GLOBAL_RAISED: AppStateKey = AppStateKey("Raised");
+
+pub const GLOBAL_MIN_INVEST_AMOUNT: AppStateKey = AppStateKey("GlobalMinInvestAmount");
+pub const GLOBAL_MAX_INVEST_AMOUNT: AppStateKey = AppStateKey("GlobalMaxInvestAmount");
+
+pub const GLOBAL_TEAM_URL: AppStateKey = AppStateKey("TeamUrl");
+
+pub const GLOBAL_SETUP_DATE: AppStateKey = AppStateKey("SetupDate");
+
+pub const LOCAL_CLAIMED_TOTAL: AppStateKey = AppStateKey("ClaimedTotal");
+pub const LOCAL_CLAIMED_INIT: AppStateKey = AppStateKey("ClaimedInit");
+pub const LOCAL_SHARES: AppStateKey = AppStateKey("Shares");
+pub const LOCAL_SIGNED_PROSPECTUS_URL: AppStateKey = AppStateKey("SignedProspectusUrl");
+pub const LOCAL_SIGNED_PROSPECTUS_HASH: AppStateKey = AppStateKey("SignedProspectusHash");
+pub const LOCAL_SIGNED_PROSPECTUS_TIMESTAMP: AppStateKey = AppStateKey("SignedProspectusTimestamp");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateScope {
+    Global,
+    Local,
+}
+
+/// Note that TEAL initializes all the keys - optional means that the value can be "unset"
+/// (empty bytes / 0), which we read as None. A missing optional key is read as unset too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Required,
+    Optional,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateKeySpec {
+    pub key: AppStateKey<'static>,
+    pub value_type: StateValueType,
+    pub presence: Presence,
+    pub scope: StateScope,
+}
+
+/// All the keys of the DAO app's state, for the current app approval version.
+/// Everything that depends on the keys (schema sizes, decoding, printing..) should be derived from here.
+#[rustfmt::skip]
+pub const DAO_STATE_SCHEMA: &[StateKeySpec] = &[
+    global(GLOBAL_TOTAL_RECEIVED, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_WITHDRAWABLE_AMOUNT, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_FUNDS_ASSET_ID, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_SHARES_ASSET_ID, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_DAO_NAME, StateValueType::Bytes, Presence::Required),
+    global(GLOBAL_DAO_DESC, StateValueType::Bytes, Presence::Optional),
+    global(GLOBAL_SHARE_PRICE, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_INVESTORS_SHARE, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_IMAGE_URL, StateValueType::Bytes, Presence::Optional),
+    global(GLOBAL_IMAGE_ASSET_ID, StateValueType::Uint, Presence::Optional),
+    global(GLOBAL_SOCIAL_MEDIA_URL, StateValueType::Bytes, Presence::Required),
+    global(GLOBAL_PROSPECTUS_URL, StateValueType::Bytes, Presence::Optional),
+    global(GLOBAL_PROSPECTUS_HASH, StateValueType::Bytes, Presence::Optional),
+    global(GLOBAL_SHARES_LOCKED, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_VERSIONS, StateValueType::Bytes, Presence::Required),
+    global(GLOBAL_TARGET, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_TARGET_END_DATE, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_RAISED, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_MIN_INVEST_AMOUNT, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_MAX_INVEST_AMOUNT, StateValueType::Uint, Presence::Required),
+    global(GLOBAL_TEAM_URL, StateValueType::Bytes, Presence::Optional),
+    global(GLOBAL_SETUP_DATE, StateValueType::Uint, Presence::Required),
+    local(LOCAL_CLAIMED_TOTAL, StateValueType::Uint, Presence::Required),
+    local(LOCAL_CLAIMED_INIT, StateValueType::Uint, Presence::Required),
+    local(LOCAL_SHARES, StateValueType::Uint, Presence::Required),
+    local(LOCAL_SIGNED_PROSPECTUS_URL, StateValueType::Bytes, Presence::Optional),
+    local(LOCAL_SIGNED_PROSPECTUS_HASH, StateValueType::Bytes, Presence::Optional),
+    local(LOCAL_SIGNED_PROSPECTUS_TIMESTAMP, StateValueType::Bytes, Presence::Optional),
+];
+
+pub const GLOBAL_SCHEMA_NUM_BYTE_SLICES: u64 =
+    count_keys(StateScope::Global, StateValueType::Bytes);
+pub const GLOBAL_SCHEMA_NUM_INTS: u64 = count_keys(StateScope::Global, StateValueType::Uint);
+
+pub const LOCAL_SCHEMA_NUM_BYTE_SLICES: u64 = count_keys(StateScope::Local, StateValueType::Bytes);
+pub const LOCAL_SCHEMA_NUM_INTS: u64 = count_keys(StateScope::Local, StateValueType::Uint);
+
+/// Keys of the current schema ([DAO_STATE_SCHEMA]) in scope
+pub fn keys_in_scope(scope: StateScope) -> impl Iterator<Item = &'static StateKeySpec> {
+    schema_keys_in_scope(DAO_STATE_SCHEMA, scope)
+}
+
+pub fn schema_keys_in_scope(
+    schema: &'static [StateKeySpec],
+    scope: StateScope,
+) -> impl Iterator<Item = &'static StateKeySpec> {
+    schema.iter().filter(move |spec| spec.scope == scope)
+}
+
+const fn global(
+    key: AppStateKey<'static>,
+    value_type: StateValueType,
+    presence: Presence,
+) -> StateKeySpec {
+    StateKeySpec {
+        key,
+        value_type,
+        presence,
+        scope: StateScope::Global,
+    }
+}
+
+const fn local(
+    key: AppStateKey<'static>,
+    value_type: StateValueType,
+    presence: Presence,
+) -> StateKeySpec {
+    StateKeySpec {
+        key,
+        value_type,
+        presence,
+        scope: StateScope::Local,
+    }
+}
+
+const fn count_keys(scope: StateScope, value_type: StateValueType) -> u64 {
+    let mut count = 0;
+    let mut i = 0;
+    while i < DAO_STATE_SCHEMA.len() {
+        let spec = &DAO_STATE_SCHEMA[i];
+        // PartialEq isn't usable in const fn, so we compare the discriminants
+        if spec.scope as u8 == scope as u8 && spec.value_type as u8 == value_type as u8 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::dao_state_schema::{
+        DAO_STATE_SCHEMA, GLOBAL_SCHEMA_NUM_BYTE_SLICES, GLOBAL_SCHEMA_NUM_INTS,
+        LOCAL_SCHEMA_NUM_BYTE_SLICES, LOCAL_SCHEMA_NUM_INTS,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_schema_keys_are_unique() {
+        let keys: HashSet<&str> = DAO_STATE_SCHEMA.iter().map(|spec| spec.key.0).collect();
+        assert_eq!(DAO_STATE_SCHEMA.len(), keys.len());
+    }
+
+    #[test]
+    fn test_schema_sizes() {
+        // these are the sizes the DAO app is created with - if they change, the TEAL has to be updated too
+        assert_eq!(8, GLOBAL_SCHEMA_NUM_BYTE_SLICES);
+        assert_eq!(14, GLOBAL_SCHEMA_NUM_INTS);
+        assert_eq!(3, LOCAL_SCHEMA_NUM_BYTE_SLICES);
+        assert_eq!(3, LOCAL_SCHEMA_NUM_INTS);
+    }
+}
@@ -0,0 +1,348 @@
+use super::{
+    app_state::{ApplicationGlobalState, ApplicationLocalStateError},
+    dao_app_state::{
+        central_investor_state_from_key_values, dao_global_state_from_key_values,
+        stored_state_layout, CentralAppGlobalState, CentralAppInvestorState,
+    },
+    dao_state_error::DaoGlobalStateError,
+};
+use crate::{
+    chain::indexer_client::{
+        app_calls_until_round, replay_global_state, replay_local_state, AppCallDeltas,
+        IndexerClient,
+    },
+    models::{dao_app_id::DaoAppId, timestamp::Timestamp},
+};
+use algonaut::core::Address;
+use anyhow::Result;
+
+/// Global state of the DAO as of `round`, rebuilt from the app calls up to the round.
+/// None if the app didn't exist at the round.
+///
+/// Each query fetches and replays all the app calls since the creation (an indexer request per 1000 calls):
+/// to query multiple rounds, fetch the calls once with [app_calls_until_round] and use [dao_global_state_from_calls].
+pub async fn dao_global_state_at_round(
+    indexer: &impl IndexerClient,
+    app_id: DaoAppId,
+    round: u64,
+) -> Result<Option<CentralAppGlobalState>, DaoGlobalStateError> {
+    let calls = app_calls_until_round(indexer, app_id.0, round)
+        .await
+        .map_err(|e| DaoGlobalStateError::Network(e.to_string()))?;
+    dao_global_state_from_calls(&calls, round)
+}
+
+/// [dao_global_state_at_round] with already fetched app calls (in confirmation order).
+/// Calls after `round` are ignored.
+pub fn dao_global_state_from_calls(
+    calls: &[AppCallDeltas],
+    round: u64,
+) -> Result<Option<CentralAppGlobalState>, DaoGlobalStateError> {
+    match replay_global_state(calls_until_round(calls, round))
+        .map_err(|e| DaoGlobalStateError::InvalidHistory(e.to_string()))?
+    {
+        Some(app) => Ok(Some(dao_global_state_from_key_values(
+            &app.global_state,
+            app.creator,
+        )?)),
+        None => Ok(None),
+    }
+}
+
+/// Global state of the DAO as of `date` (i.e. as of the last round before or at date),
+/// together with the round.
+/// None if the app didn't exist at the date.
+pub async fn dao_global_state_at_date(
+    indexer: &impl IndexerClient,
+    app_id: DaoAppId,
+    date: Timestamp,
+) -> Result<Option<(u64, CentralAppGlobalState)>, DaoGlobalStateError> {
+    let round = match round_at_date(indexer, date)
+        .await
+        .map_err(|e| DaoGlobalStateError::Network(e.to_string()))?
+    {
+        Some(round) => round,
+        None => return Ok(None),
+    };
+    Ok(dao_global_state_at_round(indexer, app_id, round)
+        .await?
+        .map(|state| (round, state)))
+}
+
+/// Investor's state as of `round`. None if the investor wasn't opted in at the round.
+/// Decoded with the layout of the app approval version the app had at the round.
+///
+/// Fetches and replays all the app calls like [dao_global_state_at_round]:
+/// to query multiple rounds or investors, use [dao_investor_state_from_calls].
+pub async fn dao_investor_state_at_round(
+    indexer: &impl IndexerClient,
+    investor: &Address,
+    app_id: DaoAppId,
+    round: u64,
+) -> Result<Option<CentralAppInvestorState>, ApplicationLocalStateError<'static>> {
+    let calls = app_calls_until_round(indexer, app_id.0, round).await?;
+    dao_investor_state_from_calls(&calls, investor, app_id, round)
+}
+
+/// [dao_investor_state_at_round] with already fetched app calls (in confirmation order).
+/// Calls after `round` are ignored.
+pub fn dao_investor_state_from_calls(
+    calls: &[AppCallDeltas],
+    investor: &Address,
+    app_id: DaoAppId,
+    round: u64,
+) -> Result<Option<CentralAppInvestorState>, ApplicationLocalStateError<'static>> {
+    let calls = calls_until_round(calls, round);
+    let key_values = match replay_local_state(calls, investor) {
+        Some(key_values) => key_values,
+        None => return Ok(None),
+    };
+    let app = replay_global_state(calls)?.ok_or_else(|| {
+        DaoGlobalStateError::InvalidHistory(format!(
+            "Investor opted in to app: {} which doesn't exist at round: {round}",
+            app_id.0
+        ))
+    })?;
+    let layout = stored_state_layout(&ApplicationGlobalState(app.global_state))?;
+    Ok(Some(central_investor_state_from_key_values(
+        &key_values,
+        layout.version,
+    )?))
+}
+
+fn calls_until_round(calls: &[AppCallDeltas], round: u64) -> &[AppCallDeltas] {
+    &calls[..calls.partition_point(|call| call.round <= round)]
+}
+
+/// Last round with a timestamp before or at `date`. None if `date` is before the first round.
+/// Binary search over block timestamps (which are non decreasing), so ~log2(current round) block lookups.
+pub async fn round_at_date(indexer: &impl IndexerClient, date: Timestamp) -> Result<Option<u64>> {
+    let current_round = indexer.current_round().await?;
+    if indexer.block_timestamp(current_round).await?.0 <= date.0 {
+        return Ok(Some(current_round));
+    }
+
+    // the current round is after date: search the last round before or at date in [0, current round)
+    let mut low = 0;
+    let mut high = current_round;
+    let mut found = None;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if indexer.block_timestamp(mid).await?.0 <= date.0 {
+            found = Some(mid);
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chain::{
+            fake_indexer_client::FakeIndexerClient,
+            indexer_client::{app_calls_until_round, AppCallCompletion, AppCallDeltas, StateDelta},
+        },
+        models::{
+            dao_app_id::DaoAppId, funds::FundsAmount, share_amount::ShareAmount,
+            timestamp::Timestamp,
+        },
+        state::{
+            app_state::uint_key_value,
+            dao_app_state::{
+                dao_global_state_to_key_values, CentralAppGlobalState, CentralAppInvestorState,
+            },
+            dao_state_schema::{GLOBAL_TOTAL_RECEIVED, LOCAL_SHARES},
+            dao_state_snapshot::{
+                dao_global_state_at_date, dao_global_state_from_calls, dao_investor_state_at_round,
+                round_at_date,
+            },
+        },
+    };
+    use algonaut::core::Address;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_round_at_date() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        // rounds 0..=100, every 4 seconds
+        for round in 0..=100 {
+            indexer.add_block(round, Timestamp(1_000 + round * 4));
+        }
+
+        assert_eq!(None, round_at_date(&indexer, Timestamp(999)).await?);
+        assert_eq!(Some(0), round_at_date(&indexer, Timestamp(1_000)).await?);
+        assert_eq!(Some(10), round_at_date(&indexer, Timestamp(1_043)).await?);
+        assert_eq!(Some(11), round_at_date(&indexer, Timestamp(1_044)).await?);
+        assert_eq!(Some(100), round_at_date(&indexer, Timestamp(5_000)).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dao_global_state_at_date() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        for round in 0..=100 {
+            indexer.add_block(round, Timestamp(1_000 + round * 4));
+        }
+        let app_id = DaoAppId(123);
+        let state = CentralAppGlobalState::for_tests();
+        let initial_deltas = dao_global_state_to_key_values(&state)?
+            .iter()
+            .map(StateDelta::from)
+            .collect();
+        indexer.add_app_call(app_id.0, creation(10, state.owner, initial_deltas));
+        // a call that doesn't change the global state
+        indexer.add_app_call(app_id.0, app_call(30, Address([2; 32]), vec![], vec![]));
+        let received_delta = StateDelta::from(&uint_key_value(&GLOBAL_TOTAL_RECEIVED, 5_000));
+        indexer.add_app_call(
+            app_id.0,
+            app_call(50, Address([2; 32]), vec![received_delta], vec![]),
+        );
+
+        // round 20: state from round 10
+        let (round, state_at_date) = dao_global_state_at_date(&indexer, app_id, Timestamp(1_080))
+            .await?
+            .unwrap();
+        assert_eq!(20, round);
+        assert_eq!(state, state_at_date);
+
+        // round 60: state from round 50
+        let (_, state_at_date) = dao_global_state_at_date(&indexer, app_id, Timestamp(1_240))
+            .await?
+            .unwrap();
+        assert_eq!(
+            CentralAppGlobalState {
+                received: FundsAmount::new(5_000),
+                ..state
+            },
+            state_at_date
+        );
+
+        // round 5: app not created yet
+        let res = dao_global_state_at_date(&indexer, app_id, Timestamp(1_020)).await?;
+        assert_eq!(None, res);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dao_global_state_from_prefetched_calls() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        let app_id = DaoAppId(123);
+        let state = CentralAppGlobalState::for_tests();
+        let initial_deltas = dao_global_state_to_key_values(&state)?
+            .iter()
+            .map(StateDelta::from)
+            .collect();
+        indexer.add_app_call(app_id.0, creation(10, state.owner, initial_deltas));
+        let received_delta = StateDelta::from(&uint_key_value(&GLOBAL_TOTAL_RECEIVED, 5_000));
+        indexer.add_app_call(
+            app_id.0,
+            app_call(50, Address([2; 32]), vec![received_delta], vec![]),
+        );
+
+        let calls = app_calls_until_round(&indexer, app_id.0, 100).await?;
+
+        assert_eq!(None, dao_global_state_from_calls(&calls, 5)?);
+        assert_eq!(
+            Some(state.clone()),
+            dao_global_state_from_calls(&calls, 49)?
+        );
+        assert_eq!(
+            Some(CentralAppGlobalState {
+                received: FundsAmount::new(5_000),
+                ..state
+            }),
+            dao_global_state_from_calls(&calls, 50)?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dao_investor_state_at_round() -> Result<()> {
+        let indexer = FakeIndexerClient::new();
+        let app_id = DaoAppId(123);
+        let investor = Address([2; 32]);
+        let other = Address([3; 32]);
+        let investor_state = CentralAppInvestorState {
+            shares: ShareAmount::new(10),
+            claimed: FundsAmount::new(100),
+            claimed_init: FundsAmount::new(100),
+            signed_prospectus: None,
+        };
+        let local_deltas: Vec<StateDelta> = investor_state
+            .to_key_values_for_tests()
+            .iter()
+            .map(StateDelta::from)
+            .collect();
+
+        indexer.add_app_call(app_id.0, creation(1, Address([1; 32]), vec![]));
+        indexer.add_app_call(app_id.0, opt_in(10, investor, local_deltas.clone()));
+        indexer.add_app_call(app_id.0, opt_in(15, other, local_deltas));
+        let shares_delta = StateDelta::from(&uint_key_value(&LOCAL_SHARES, 30));
+        indexer.add_app_call(
+            app_id.0,
+            app_call(20, investor, vec![], vec![(investor, vec![shares_delta])]),
+        );
+        indexer.add_app_call(
+            app_id.0,
+            AppCallDeltas {
+                completion: AppCallCompletion::CloseOut,
+                ..app_call(30, investor, vec![], vec![])
+            },
+        );
+
+        let at_round = |round| dao_investor_state_at_round(&indexer, &investor, app_id, round);
+
+        assert_eq!(None, at_round(5).await?);
+        assert_eq!(Some(investor_state.clone()), at_round(10).await?);
+        assert_eq!(
+            Some(CentralAppInvestorState {
+                shares: ShareAmount::new(30),
+                ..investor_state.clone()
+            }),
+            at_round(25).await?
+        );
+        assert_eq!(None, at_round(30).await?);
+        // the other investor's state isn't affected
+        assert_eq!(
+            Some(investor_state),
+            dao_investor_state_at_round(&indexer, &other, app_id, 30).await?
+        );
+
+        Ok(())
+    }
+
+    fn app_call(
+        round: u64,
+        sender: Address,
+        global_delta: Vec<StateDelta>,
+        local_deltas: Vec<(Address, Vec<StateDelta>)>,
+    ) -> AppCallDeltas {
+        AppCallDeltas {
+            round,
+            sender,
+            completion: AppCallCompletion::NoOp,
+            creates_app: false,
+            global_delta,
+            local_deltas,
+        }
+    }
+
+    fn creation(round: u64, creator: Address, global_delta: Vec<StateDelta>) -> AppCallDeltas {
+        AppCallDeltas {
+            creates_app: true,
+            ..app_call(round, creator, global_delta, vec![])
+        }
+    }
+
+    fn opt_in(round: u64, sender: Address, local_delta: Vec<StateDelta>) -> AppCallDeltas {
+        AppCallDeltas {
+            completion: AppCallCompletion::OptIn,
+            ..app_call(round, sender, vec![], vec![(sender, local_delta)])
+        }
+    }
+}
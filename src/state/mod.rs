@@ -1,2 +1,8 @@
 pub mod app_state;
 pub mod dao_app_state;
+pub mod dao_state_error;
+pub mod dao_state_schema;
+pub mod dao_provenance;
+pub mod prospectus_verification;
+pub mod dao_state_snapshot;
+pub mod dao_history;
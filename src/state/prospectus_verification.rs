@@ -0,0 +1,100 @@
+use super::dao_app_state::{prospectus_hash, Prospectus, SignedProspectus};
+
+/// Result of comparing a document with a stored prospectus hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentCheck {
+    Matches,
+    Mismatch,
+    /// There's no prospectus to compare with
+    NotSet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProspectusVerification {
+    /// Hash of the document, encoded like the stored hashes
+    pub document_hash: String,
+    /// Document vs the DAO's (current) prospectus
+    pub dao_prospectus: DocumentCheck,
+    /// Document vs the prospectus the investor signed
+    pub signed_prospectus: DocumentCheck,
+    /// Whether the investor signed a prospectus that's not the DAO's current one
+    /// (false if the investor didn't sign or the DAO has no prospectus)
+    pub signed_outdated: bool,
+}
+
+/// Verifies a prospectus document (e.g. downloaded from the prospectus url)
+/// against the DAO's prospectus and the one signed by the investor.
+pub fn verify_prospectus(
+    document: &[u8],
+    dao_prospectus: Option<&Prospectus>,
+    signed_prospectus: Option<&SignedProspectus>,
+) -> ProspectusVerification {
+    let document_hash = prospectus_hash(document);
+
+    let signed_outdated = match (dao_prospectus, signed_prospectus) {
+        (Some(current), Some(signed)) => current.hash != signed.hash,
+        _ => false,
+    };
+
+    ProspectusVerification {
+        dao_prospectus: check(&document_hash, dao_prospectus.map(|p| p.hash.as_str())),
+        signed_prospectus: check(&document_hash, signed_prospectus.map(|p| p.hash.as_str())),
+        signed_outdated,
+        document_hash,
+    }
+}
+
+fn check(document_hash: &str, stored_hash: Option<&str>) -> DocumentCheck {
+    match stored_hash {
+        Some(hash) if hash == document_hash => DocumentCheck::Matches,
+        Some(_) => DocumentCheck::Mismatch,
+        None => DocumentCheck::NotSet,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::timestamp::Timestamp,
+        state::{
+            dao_app_state::{Prospectus, SignedProspectus},
+            prospectus_verification::{verify_prospectus, DocumentCheck},
+        },
+    };
+
+    #[test]
+    fn test_verify_current_prospectus() {
+        let document = b"prospectus v2";
+        let dao_prospectus = Prospectus::new(document, "https://prospectus".to_owned());
+        let signed = signed_prospectus(b"prospectus v2");
+
+        let res = verify_prospectus(document, Some(&dao_prospectus), Some(&signed));
+
+        assert_eq!(dao_prospectus.hash, res.document_hash);
+        assert_eq!(DocumentCheck::Matches, res.dao_prospectus);
+        assert_eq!(DocumentCheck::Matches, res.signed_prospectus);
+        assert!(!res.signed_outdated);
+    }
+
+    #[test]
+    fn test_verify_detects_outdated_signed_prospectus() {
+        let document = b"prospectus v2";
+        let dao_prospectus = Prospectus::new(document, "https://prospectus".to_owned());
+        let signed = signed_prospectus(b"prospectus v1");
+
+        let res = verify_prospectus(document, Some(&dao_prospectus), Some(&signed));
+
+        assert_eq!(DocumentCheck::Matches, res.dao_prospectus);
+        assert_eq!(DocumentCheck::Mismatch, res.signed_prospectus);
+        assert!(res.signed_outdated);
+    }
+
+    fn signed_prospectus(document: &[u8]) -> SignedProspectus {
+        let prospectus = Prospectus::new(document, "https://prospectus".to_owned());
+        SignedProspectus {
+            hash: prospectus.hash,
+            url: prospectus.url,
+            timestamp: Timestamp(1_600_000_000),
+        }
+    }
+}
@@ -1,6 +1,11 @@
-use algonaut::transaction::SignedTransaction;
+use algonaut::{core::Address, transaction::SignedTransaction};
 use anyhow::{anyhow, Result};
-use std::fs;
+use data_encoding::HEXLOWER;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::{self, Display, Formatter},
+    fs,
+};
 
 const TEAL_PROJECT_PATH: &str = "../teal";
 
@@ -50,18 +55,198 @@ fn load_file_bytes(folder: &str, file_name: &str) -> Result<Vec<u8>> {
     Ok(fs::read(path)?)
 }
 
-/// IMPORTANT: keys must not be contained in other keys (e.g: "precision" and "precision_square")
-/// this currently uses normal text replacement so such keys will lead to errors
+/// Prefix of the template placeholders, e.g. TMPL_CAPI_APP_ID
+const PLACEHOLDER_PREFIX: &str = "TMPL_";
+
+/// A value to insert in a template, formatted as the TEAL literal for its type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TemplateValue {
+    /// For `addr TMPL_..`
+    Address(Address),
+    /// For `int TMPL_..`
+    U64(u64),
+    /// For `byte TMPL_..` (rendered as hex)
+    Bytes(Vec<u8>),
+}
+
+impl TemplateValue {
+    fn to_teal(&self) -> String {
+        match self {
+            TemplateValue::Address(address) => address.to_string(),
+            TemplateValue::U64(value) => value.to_string(),
+            TemplateValue::Bytes(bytes) => format!("0x{}", HEXLOWER.encode(bytes)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    InvalidUtf8,
+    /// Not a valid placeholder name (TMPL_ followed by uppercase letters, digits or _)
+    InvalidKey(String),
+    DuplicateKey(String),
+    /// Placeholders in the template without a value
+    MissingValues(Vec<String>),
+    /// Values without a placeholder in the template - likely a typo or a template of a different version
+    UnusedValues(Vec<String>),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::InvalidUtf8 => write!(f, "Template isn't valid utf-8"),
+            TemplateError::InvalidKey(key) => write!(f, "Invalid template key: {key}"),
+            TemplateError::DuplicateKey(key) => write!(f, "Duplicate template key: {key}"),
+            TemplateError::MissingValues(keys) => {
+                write!(f, "No values for template placeholders: {keys:?}")
+            }
+            TemplateError::UnusedValues(keys) => {
+                write!(f, "Template has no placeholders for keys: {keys:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateToken<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+impl TealSourceTemplate {
+    /// Names of the placeholders in the template (including the TMPL_ prefix)
+    pub fn placeholders(&self) -> Result<BTreeSet<String>, TemplateError> {
+        Ok(tokenize(self.as_str()?)
+            .into_iter()
+            .filter_map(|token| match token {
+                TemplateToken::Placeholder(name) => Some(name.to_owned()),
+                TemplateToken::Text(_) => None,
+            })
+            .collect())
+    }
+
+    fn as_str(&self) -> Result<&str, TemplateError> {
+        std::str::from_utf8(&self.0).map_err(|_| TemplateError::InvalidUtf8)
+    }
+}
+
+/// Replaces the placeholders of the template with the values.
+/// All the placeholders must have a value and all the values must have a placeholder.
+pub fn render_template(
+    template: &TealSourceTemplate,
+    key_values: &[(&str, TemplateValue)],
+) -> Result<TealSource, TemplateError> {
+    render_tokens(template, key_values, TemplateValue::to_teal)
+}
+
+/// Values are inserted as they are, so they have to be valid TEAL literals.
+/// Unlike the previous string replacement, keys must be placeholders (TMPL_..) and have to match the template exactly.
+#[deprecated(note = "use render_template, which takes typed values")]
 pub fn render_template_new(
     template: &TealSourceTemplate,
     key_values: &[(&str, &str)],
 ) -> Result<TealSource> {
-    // this has not been tuned at all for performance - probably can be improved
-    let mut teal_str = std::str::from_utf8(&template.0)?.to_owned();
+    Ok(render_tokens(template, key_values, |value| {
+        value.to_string()
+    })?)
+}
+
+fn render_tokens<V>(
+    template: &TealSourceTemplate,
+    key_values: &[(&str, V)],
+    to_teal: impl Fn(&V) -> String,
+) -> Result<TealSource, TemplateError> {
+    let mut values = HashMap::new();
     for (key, value) in key_values {
-        teal_str = teal_str.replace(key, value);
+        if !is_placeholder(key) {
+            return Err(TemplateError::InvalidKey(key.to_string()));
+        }
+        if values.insert(*key, value).is_some() {
+            return Err(TemplateError::DuplicateKey(key.to_string()));
+        }
+    }
+
+    let tokens = tokenize(template.as_str()?);
+
+    let mut missing = BTreeSet::new();
+    let mut used = HashSet::new();
+    let mut teal = String::new();
+    for token in tokens {
+        match token {
+            TemplateToken::Text(text) => teal.push_str(text),
+            TemplateToken::Placeholder(name) => match values.get(name) {
+                Some(value) => {
+                    used.insert(name);
+                    teal.push_str(&to_teal(value));
+                }
+                None => {
+                    missing.insert(name.to_owned());
+                }
+            },
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(TemplateError::MissingValues(missing.into_iter().collect()));
+    }
+    let mut unused: Vec<String> = key_values
+        .iter()
+        .map(|(key, _)| *key)
+        .filter(|key| !used.contains(key))
+        .map(|key| key.to_owned())
+        .collect();
+    if !unused.is_empty() {
+        unused.sort();
+        return Err(TemplateError::UnusedValues(unused));
+    }
+
+    Ok(TealSource(teal.into_bytes()))
+}
+
+/// Splits the template in text and placeholders.
+/// A placeholder is the prefix followed by the longest run of name characters, so names can't be confused
+/// with names they contain (e.g. TMPL_PRECISION and TMPL_PRECISION_SQUARE).
+fn tokenize(template: &str) -> Vec<TemplateToken<'_>> {
+    let mut tokens = vec![];
+    let mut text_start = 0;
+    let mut search_from = 0;
+    while let Some(offset) = template[search_from..].find(PLACEHOLDER_PREFIX) {
+        let start = search_from + offset;
+        let name_start = start + PLACEHOLDER_PREFIX.len();
+        let name_len = template[name_start..]
+            .find(|c: char| !is_placeholder_char(c))
+            .unwrap_or(template.len() - name_start);
+        let end = name_start + name_len;
+
+        // the prefix has to start a word (e.g. not MY_TMPL_X)
+        let starts_word = !template[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+        if name_len > 0 && starts_word {
+            if text_start < start {
+                tokens.push(TemplateToken::Text(&template[text_start..start]));
+            }
+            tokens.push(TemplateToken::Placeholder(&template[start..end]));
+            text_start = end;
+        }
+        search_from = end;
+    }
+    if text_start < template.len() {
+        tokens.push(TemplateToken::Text(&template[text_start..]));
     }
-    Ok(TealSource(teal_str.as_bytes().to_vec()))
+    tokens
+}
+
+fn is_placeholder(key: &str) -> bool {
+    key.strip_prefix(PLACEHOLDER_PREFIX)
+        .is_some_and(|name| !name.is_empty() && name.chars().all(is_placeholder_char))
+}
+
+fn is_placeholder_char(c: char) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'
 }
 
 /// file_name without .teal
@@ -83,3 +268,101 @@ fn debug_teal_internal(txs: &[SignedTransaction], folder: &str, file_name: &str)
     tealdbg::launch_default(txs, &in_teal_dir(&format!("{folder}/{file_name}.teal")))
         .map_err(|e| anyhow!(e))
 }
+
+#[cfg(test)]
+mod tests {
+    #[allow(deprecated)]
+    use crate::teal::render_template_new;
+    use crate::teal::{render_template, TealSourceTemplate, TemplateError, TemplateValue};
+    use anyhow::Result;
+    use std::collections::BTreeSet;
+
+    fn template(teal: &str) -> TealSourceTemplate {
+        TealSourceTemplate(teal.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_render_template() -> Result<()> {
+        let template = template(
+            "int TMPL_PRECISION\nint TMPL_PRECISION_SQUARE\nbyte TMPL_HASH\n==\nint TMPL_PRECISION",
+        );
+
+        let rendered = render_template(
+            &template,
+            &[
+                ("TMPL_PRECISION_SQUARE", TemplateValue::U64(100)),
+                ("TMPL_PRECISION", TemplateValue::U64(10)),
+                ("TMPL_HASH", TemplateValue::Bytes(vec![0xab, 0x01])),
+            ],
+        )?;
+
+        assert_eq!(
+            "int 10\nint 100\nbyte 0xab01\n==\nint 10",
+            rendered.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_placeholders() -> Result<()> {
+        let template =
+            template("addr TMPL_OWNER // TMPL_OWNER\nint MY_TMPL_X\nint TMPL_\nint TMPL_A1");
+
+        let expected: BTreeSet<String> = ["TMPL_OWNER", "TMPL_A1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(expected, template.placeholders()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let template = template("int TMPL_A\nint TMPL_B");
+
+        assert_eq!(
+            Err(TemplateError::MissingValues(vec!["TMPL_B".to_owned()])),
+            render_template(&template, &[("TMPL_A", TemplateValue::U64(1))])
+        );
+        assert_eq!(
+            Err(TemplateError::UnusedValues(vec!["TMPL_C".to_owned()])),
+            render_template(
+                &template,
+                &[
+                    ("TMPL_A", TemplateValue::U64(1)),
+                    ("TMPL_B", TemplateValue::U64(2)),
+                    ("TMPL_C", TemplateValue::U64(3)),
+                ]
+            )
+        );
+        assert_eq!(
+            Err(TemplateError::InvalidKey("TMPL_a".to_owned())),
+            render_template(&template, &[("TMPL_a", TemplateValue::U64(1))])
+        );
+        assert_eq!(
+            Err(TemplateError::DuplicateKey("TMPL_A".to_owned())),
+            render_template(
+                &template,
+                &[
+                    ("TMPL_A", TemplateValue::U64(1)),
+                    ("TMPL_A", TemplateValue::U64(1))
+                ]
+            )
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_render_template_new_inserts_raw_values() -> Result<()> {
+        let template = template("int TMPL_PRECISION\nint TMPL_PRECISION_SQUARE");
+
+        let teal = render_template_new(
+            &template,
+            &[("TMPL_PRECISION", "10"), ("TMPL_PRECISION_SQUARE", "100")],
+        )?;
+
+        assert_eq!("int 10\nint 100", teal.to_string());
+        assert!(render_template_new(&template, &[("TMPL_PRECISION", "10")]).is_err());
+        Ok(())
+    }
+}
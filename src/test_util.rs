@@ -0,0 +1,174 @@
+//! Fixtures and stubs shared by the unit tests.
+//! Suites override only the fields they assert on, e.g. `..CentralAppGlobalState::for_tests()`.
+
+use crate::{
+    api::{
+        contract::{Contract, ContractRenderer},
+        teal_api::TealTemplateLoader,
+        version::{Version, VersionedTealSourceTemplate, Versions},
+    },
+    models::{
+        funds::{FundsAmount, FundsAssetId},
+        share_amount::ShareAmount,
+        timestamp::Timestamp,
+    },
+    state::{
+        app_state::{bytes_key_value, uint_key_value},
+        dao_app_state::{
+            dao_global_state_to_key_values, CentralAppGlobalState, CentralAppInvestorState,
+        },
+        dao_state_schema::{
+            GLOBAL_SCHEMA_NUM_BYTE_SLICES, GLOBAL_SCHEMA_NUM_INTS, LOCAL_CLAIMED_INIT,
+            LOCAL_CLAIMED_TOTAL, LOCAL_SCHEMA_NUM_BYTE_SLICES, LOCAL_SCHEMA_NUM_INTS, LOCAL_SHARES,
+            LOCAL_SIGNED_PROSPECTUS_HASH, LOCAL_SIGNED_PROSPECTUS_TIMESTAMP,
+            LOCAL_SIGNED_PROSPECTUS_URL,
+        },
+    },
+    teal::{TealSource, TealSourceTemplate},
+};
+use algonaut::{
+    core::Address,
+    model::algod::v2::{
+        Application, ApplicationLocalState, ApplicationParams, ApplicationStateSchema, TealKeyValue,
+    },
+};
+use anyhow::Result;
+use std::convert::TryInto;
+
+impl CentralAppGlobalState {
+    /// A valid state for tests: a DAO in its fundraising phase, without the optional fields.
+    pub(crate) fn for_tests() -> CentralAppGlobalState {
+        CentralAppGlobalState {
+            received: FundsAmount::new(0),
+            available: FundsAmount::new(0),
+            app_approval_version: Version(1),
+            app_clear_version: Version(1),
+            funds_asset_id: FundsAssetId(123),
+            shares_asset_id: 456,
+            project_name: "my dao".to_owned(),
+            project_desc_url: None,
+            share_price: FundsAmount::new(10),
+            // unwrap: valid percentage
+            investors_share: rust_decimal::Decimal::new(4, 1).try_into().unwrap(),
+            image_nft: None,
+            social_media_url: "https://social".to_owned(),
+            prospectus: None,
+            owner: Address([1; 32]),
+            locked_shares: ShareAmount::new(0),
+            min_funds_target: FundsAmount::new(1_000),
+            min_funds_target_end_date: Timestamp(1_000),
+            raised: FundsAmount::new(0),
+            setup_date: Timestamp(0),
+            min_invest_amount: ShareAmount::new(1),
+            max_invest_amount: ShareAmount::new(100),
+            team_url: None,
+        }
+    }
+}
+
+impl CentralAppInvestorState {
+    /// Local state of the investor, like TEAL stores it
+    pub(crate) fn to_key_values_for_tests(&self) -> Vec<TealKeyValue> {
+        let (url, hash, timestamp) = match &self.signed_prospectus {
+            Some(p) => (
+                p.url.as_bytes().to_vec(),
+                p.hash.as_bytes().to_vec(),
+                p.timestamp.0.to_be_bytes().to_vec(),
+            ),
+            None => (vec![], vec![], vec![]),
+        };
+        vec![
+            uint_key_value(&LOCAL_SHARES, self.shares.val()),
+            uint_key_value(&LOCAL_CLAIMED_TOTAL, self.claimed.val()),
+            uint_key_value(&LOCAL_CLAIMED_INIT, self.claimed_init.val()),
+            bytes_key_value(&LOCAL_SIGNED_PROSPECTUS_URL, url),
+            bytes_key_value(&LOCAL_SIGNED_PROSPECTUS_HASH, hash),
+            bytes_key_value(&LOCAL_SIGNED_PROSPECTUS_TIMESTAMP, timestamp),
+        ]
+    }
+}
+
+/// The DAO app with the state, created with the schema of the current layout
+pub(crate) fn dao_app(app_id: u64, state: &CentralAppGlobalState) -> Result<Application> {
+    Ok(Application {
+        id: app_id,
+        params: ApplicationParams {
+            approval_program: vec![],
+            clear_state_program: vec![],
+            creator: state.owner,
+            global_state: dao_global_state_to_key_values(state)?,
+            global_state_schema: Some(ApplicationStateSchema {
+                num_byte_slice: GLOBAL_SCHEMA_NUM_BYTE_SLICES,
+                num_uint: GLOBAL_SCHEMA_NUM_INTS,
+            }),
+            local_state_schema: Some(ApplicationStateSchema {
+                num_byte_slice: LOCAL_SCHEMA_NUM_BYTE_SLICES,
+                num_uint: LOCAL_SCHEMA_NUM_INTS,
+            }),
+        },
+    })
+}
+
+/// Local state of an investor opted in to the DAO app
+pub(crate) fn capi_local_state(
+    app_id: u64,
+    state: &CentralAppInvestorState,
+) -> ApplicationLocalState {
+    ApplicationLocalState {
+        id: app_id,
+        key_value: state.to_key_values_for_tests(),
+        schema: ApplicationStateSchema {
+            num_byte_slice: LOCAL_SCHEMA_NUM_BYTE_SLICES,
+            num_uint: LOCAL_SCHEMA_NUM_INTS,
+        },
+    }
+}
+
+/// Templates are just "{contract:?} v{version}", for the versions up to `max_version`
+pub(crate) struct InMemoryLoader {
+    pub latest: Versions,
+    /// Can be newer than `latest` (e.g. a version that isn't released yet)
+    pub max_version: Version,
+}
+
+impl InMemoryLoader {
+    /// Only v1 of the contracts
+    pub(crate) fn v1() -> InMemoryLoader {
+        InMemoryLoader {
+            latest: Versions {
+                app_approval: Version(1),
+                app_clear: Version(1),
+            },
+            max_version: Version(1),
+        }
+    }
+}
+
+impl TealTemplateLoader for InMemoryLoader {
+    fn template(
+        &self,
+        contract: Contract,
+        version: Version,
+    ) -> Result<Option<VersionedTealSourceTemplate>> {
+        Ok((version <= self.max_version).then(|| {
+            let teal = format!("{contract:?} v{}", version.0);
+            VersionedTealSourceTemplate::new(TealSourceTemplate(teal.into_bytes()), version)
+        }))
+    }
+
+    fn latest_version(&self, contract: Contract) -> Option<Version> {
+        match contract {
+            Contract::DaoAppApproval => Some(self.latest.app_approval),
+            Contract::DaoAppClear | Contract::DaoCustomer => Some(self.latest.app_clear),
+        }
+    }
+}
+
+/// Renders the templates as they are
+pub(crate) struct PassthroughRenderer;
+
+impl ContractRenderer for PassthroughRenderer {
+    fn render(&self, _: Contract, template: &VersionedTealSourceTemplate) -> Result<TealSource> {
+        Ok(TealSource(template.template.0.clone()))
+    }
+}
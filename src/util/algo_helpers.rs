@@ -1,12 +1,11 @@
 use algonaut::{
-    algod::v2::Algod,
     core::{MicroAlgos, SuggestedTransactionParams},
     model::algod::v2::{PendingTransaction, TransactionResponse},
     transaction::{SignedTransaction, Transaction},
 };
 use anyhow::{anyhow, Result};
 
-use crate::models::tx_id::TxId;
+use crate::{chain::chain_client::ChainClient, models::tx_id::TxId};
 
 use super::network_util::wait_for_pending_transaction;
 
@@ -23,24 +22,70 @@ pub fn calculate_total_fee(
     Ok(total_fee)
 }
 
-pub async fn send_tx_and_wait(algod: &Algod, tx: &SignedTransaction) -> Result<PendingTransaction> {
+pub async fn send_tx_and_wait(
+    algod: &impl ChainClient,
+    tx: &SignedTransaction,
+) -> Result<PendingTransaction> {
     let res = algod.broadcast_signed_transaction(tx).await?;
     wait_for_p_tx(algod, res).await
 }
 
 pub async fn send_txs_and_wait(
-    algod: &Algod,
+    algod: &impl ChainClient,
     txs: &[SignedTransaction],
 ) -> Result<PendingTransaction> {
     let res = algod.broadcast_signed_transactions(txs).await?;
     wait_for_p_tx(algod, res).await
 }
 
-async fn wait_for_p_tx(algod: &Algod, response: TransactionResponse) -> Result<PendingTransaction> {
+async fn wait_for_p_tx(
+    algod: &impl ChainClient,
+    response: TransactionResponse,
+) -> Result<PendingTransaction> {
     wait_for_p_tx_with_id(algod, &response.tx_id.parse()?).await
 }
 
-pub async fn wait_for_p_tx_with_id(algod: &Algod, tx_id: &TxId) -> Result<PendingTransaction> {
+pub async fn wait_for_p_tx_with_id(
+    algod: &impl ChainClient,
+    tx_id: &TxId,
+) -> Result<PendingTransaction> {
     let p_tx = wait_for_pending_transaction(algod, tx_id).await?;
     p_tx.ok_or_else(|| anyhow!("Pending tx couldn't be retrieved, tx id: {:?}", tx_id))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chain::fake_chain_client::FakeChainClient, models::tx_id::TxId,
+        util::algo_helpers::send_tx_and_wait,
+    };
+    use algonaut::{
+        crypto::HashDigest,
+        model::algod::v2::PendingTransaction,
+        transaction::{SignedTransaction, Transaction},
+    };
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_send_tx_and_wait() -> Result<()> {
+        let algod = FakeChainClient::new();
+        let tx_id = TxId::from(HashDigest([1; 32])).to_string();
+        let tx = SignedTransaction {
+            transaction: Transaction,
+            transaction_id: tx_id.clone(),
+        };
+        algod.add_pending_transaction(
+            &tx_id,
+            PendingTransaction {
+                confirmed_round: Some(10),
+                pool_error: "".to_owned(),
+            },
+        );
+
+        let pending_tx = send_tx_and_wait(&algod, &tx).await?;
+
+        assert_eq!(Some(10), pending_tx.confirmed_round);
+        assert_eq!(vec![tx], algod.broadcasted());
+        Ok(())
+    }
+}
@@ -1,15 +1,24 @@
-use algonaut::{algod::v2::Algod, error::ServiceError, model::algod::v2::PendingTransaction};
+use algonaut::model::algod::v2::PendingTransaction;
+use anyhow::{anyhow, Result};
 use instant::Instant;
 use std::time::Duration;
 
-use crate::models::tx_id::TxId;
+use crate::{chain::chain_client::ChainClient, models::tx_id::TxId};
 
 /// Utility function to wait on a transaction to be confirmed
 pub async fn wait_for_pending_transaction(
-    algod: &Algod,
+    algod: &impl ChainClient,
     tx_id: &TxId,
-) -> Result<Option<PendingTransaction>, ServiceError> {
-    let timeout = Duration::from_secs(60);
+) -> Result<Option<PendingTransaction>> {
+    wait_for_pending_transaction_with_timeout(algod, tx_id, Duration::from_secs(60)).await
+}
+
+/// Returns None on timeout, and an error if the node removed the transaction from the pool (e.g. it was rejected)
+pub async fn wait_for_pending_transaction_with_timeout(
+    algod: &impl ChainClient,
+    tx_id: &TxId,
+    timeout: Duration,
+) -> Result<Option<PendingTransaction>> {
     let start = Instant::now();
     log::debug!("Start waiting for pending tx confirmation..");
     loop {
@@ -19,6 +28,12 @@ pub async fn wait_for_pending_transaction(
         // If the transaction has been confirmed or we time out, exit.
         if pending_transaction.confirmed_round.is_some() {
             return Ok(Some(pending_transaction));
+        } else if !pending_transaction.pool_error.is_empty() {
+            return Err(anyhow!(
+                "Pending tx: {} failed: {}",
+                tx_id.to_string(),
+                pending_transaction.pool_error
+            ));
         } else if start.elapsed() >= timeout {
             log::debug!("Timeout waiting for pending tx");
             return Ok(None);
@@ -36,3 +51,65 @@ pub async fn sleep(ms: u32) {
 pub async fn sleep(ms: u32) {
     futures_timer::Delay::new(std::time::Duration::from_millis(ms as u64)).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chain::fake_chain_client::FakeChainClient,
+        models::tx_id::TxId,
+        util::network_util::{
+            wait_for_pending_transaction, wait_for_pending_transaction_with_timeout,
+        },
+    };
+    use algonaut::{crypto::HashDigest, model::algod::v2::PendingTransaction};
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_returns_confirmed_tx() -> Result<()> {
+        let algod = FakeChainClient::new();
+        let tx_id = tx_id();
+        algod.add_pending_transaction(&tx_id.to_string(), pending_tx(Some(10), ""));
+
+        let pending_tx = wait_for_pending_transaction(&algod, &tx_id).await?;
+
+        assert_eq!(Some(10), pending_tx.and_then(|tx| tx.confirmed_round));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pool_error_is_an_error() {
+        let algod = FakeChainClient::new();
+        let tx_id = tx_id();
+        algod.add_pending_transaction(&tx_id.to_string(), pending_tx(None, "overspend"));
+
+        let res = wait_for_pending_transaction(&algod, &tx_id).await;
+
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_returns_none_on_timeout() -> Result<()> {
+        let algod = FakeChainClient::new();
+        let tx_id = tx_id();
+        algod.add_pending_transaction(&tx_id.to_string(), pending_tx(None, ""));
+
+        let pending_tx =
+            wait_for_pending_transaction_with_timeout(&algod, &tx_id, Duration::from_millis(300))
+                .await?;
+
+        assert!(pending_tx.is_none());
+        Ok(())
+    }
+
+    fn tx_id() -> TxId {
+        TxId::from(HashDigest([1; 32]))
+    }
+
+    fn pending_tx(confirmed_round: Option<u64>, pool_error: &str) -> PendingTransaction {
+        PendingTransaction {
+            confirmed_round,
+            pool_error: pool_error.to_owned(),
+        }
+    }
+}